@@ -0,0 +1,619 @@
+//! Engine builds from source.
+
+use std::path::{Path, PathBuf};
+
+use gdpm_io::{Error, IoAdapter};
+use gdpm_types::version::{GodotVersion, GodotVersionKind};
+use slugify::slugify;
+use tracing::{debug, info};
+
+use crate::{
+    config::{GodotDir, UserDir},
+    engine::{EngineHandler, EngineInfo, ENGINE_DIR, EXPORT_TEMPLATES_VERSION_FILE},
+    error::BuildError,
+};
+
+/// Official engine repository.
+pub const DEFAULT_REPOSITORY: &str = "https://github.com/godotengine/godot.git";
+/// Engine sources folder name, in the global directory.
+pub const SOURCES_DIR: &str = "sources";
+
+const GIT_PROGRAM: &str = "git";
+const SCONS_PROGRAM: &str = "scons";
+const VERSION_INFO_FILE: &str = "version.py";
+const BIN_DIR: &str = "bin";
+const GODOT_EXECUTABLE_NAME: &str = "godot";
+
+/// Build options.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Additional SCons arguments (e.g. `production=yes`)
+    pub scons_args: Vec<String>,
+    /// Also build export templates
+    pub templates: bool,
+}
+
+/// Build output.
+#[derive(Debug, Clone)]
+pub struct BuildOutput {
+    /// Registered engine
+    pub engine: EngineInfo,
+    /// Export templates path, if built
+    pub templates_path: Option<PathBuf>,
+}
+
+/// Engine build handler.
+pub struct BuildHandler<'a, I: IoAdapter> {
+    io_adapter: &'a I,
+}
+
+impl<'a, I: IoAdapter> BuildHandler<'a, I> {
+    /// Creates a new build handler.
+    pub fn new(io_adapter: &'a I) -> Self {
+        Self { io_adapter }
+    }
+
+    /// Get source folder for a repository.
+    pub fn get_source_directory(&self, repository: &str) -> Result<PathBuf, Error> {
+        let udir = UserDir::new(self.io_adapter);
+        Ok(udir
+            .get_or_create_directory(Path::new(SOURCES_DIR))?
+            .join(slugify!(repository)))
+    }
+
+    /// Clone repository (if needed) and checkout a git reference (branch, tag or commit).
+    pub fn checkout(&self, repository: &str, git_ref: &str) -> Result<PathBuf, BuildError> {
+        let source_dir = self.get_source_directory(repository)?;
+        let parent = source_dir
+            .parent()
+            .ok_or_else(|| Error::NoParentFolder(source_dir.clone()))?;
+
+        if !self.io_adapter.path_exists(&source_dir) {
+            info!("Cloning repository '{}' ...", repository);
+            self.io_adapter.run_command(
+                GIT_PROGRAM,
+                &[
+                    "clone".into(),
+                    repository.into(),
+                    source_dir.display().to_string(),
+                ],
+                parent,
+            )?;
+        }
+
+        info!("Checking out '{}' ...", git_ref);
+        self.io_adapter.run_command(
+            GIT_PROGRAM,
+            &["fetch".into(), repository.into(), git_ref.into()],
+            &source_dir,
+        )?;
+        self.io_adapter.run_command(
+            GIT_PROGRAM,
+            &[
+                "checkout".into(),
+                "--force".into(),
+                "--detach".into(),
+                "FETCH_HEAD".into(),
+            ],
+            &source_dir,
+        )?;
+
+        Ok(source_dir)
+    }
+
+    /// Get engine version for a source folder, using a custom build kind.
+    pub fn get_build_version(
+        &self,
+        source_dir: &Path,
+        kind: GodotVersionKind,
+    ) -> Result<GodotVersion, BuildError> {
+        let version_path = source_dir.join(VERSION_INFO_FILE);
+        let contents = self.io_adapter.read_file_to_string(&version_path)?;
+        let number = parse_version_info(&contents)
+            .ok_or_else(|| BuildError::MissingVersionInfo(version_path.clone()))?;
+
+        Ok(GodotVersion::new(&number, kind, false))
+    }
+
+    /// Build editor (and export templates) from a source folder, and register it as `version`.
+    ///
+    /// Existing binaries for this version are replaced.
+    pub fn build(
+        &self,
+        source_dir: &Path,
+        version: &GodotVersion,
+        options: &BuildOptions,
+    ) -> Result<BuildOutput, BuildError> {
+        let bin_dir = source_dir.join(BIN_DIR);
+        // Start from an empty folder to only collect binaries from this build
+        if self.io_adapter.path_exists(&bin_dir) {
+            self.io_adapter.remove_dir_all(&bin_dir)?;
+        }
+
+        let mut targets = vec![get_editor_scons_args(version)];
+        if options.templates {
+            targets.extend(get_templates_scons_args(version));
+        }
+        for target in targets {
+            let args: Vec<_> = target
+                .iter()
+                .map(|a| a.to_string())
+                .chain(options.scons_args.iter().cloned())
+                .collect();
+            info!("Building '{}' ...", args.join(" "));
+            self.io_adapter
+                .run_command(SCONS_PROGRAM, &args, source_dir)?;
+        }
+
+        let mut binaries: Vec<_> = self
+            .io_adapter
+            .read_dir(&bin_dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| self.io_adapter.path_is_file(p))
+            .filter(|p| !is_debug_symbols(p))
+            .collect();
+        binaries.sort();
+
+        let editor_binary = binaries
+            .iter()
+            .find(|p| is_editor_binary(p) && !get_file_name(p).contains(".console."))
+            .ok_or_else(|| BuildError::MissingEditorBinary(bin_dir.clone()))?;
+        let engine_path = self.install_editor(editor_binary, version)?;
+
+        let templates_path = if options.templates {
+            let templates: Vec<_> = binaries.iter().filter(|p| !is_editor_binary(p)).collect();
+            Some(self.install_templates(&templates, version)?)
+        } else {
+            None
+        };
+
+        let ehandler = EngineHandler::new(self.io_adapter);
+        let mut engine = EngineInfo::new(self.io_adapter, version.clone(), engine_path)?;
+        // Custom builds report a generic build name, only keep their hash
+        engine.hash = match ehandler.detect_version(&engine.path) {
+            Ok(build) => build.hash,
+            Err(e) => {
+                debug!("Could not detect engine build: {}", e);
+                None
+            }
+        };
+        ehandler.register(engine.clone())?;
+
+        Ok(BuildOutput {
+            engine,
+            templates_path,
+        })
+    }
+
+    fn install_editor(&self, binary: &Path, version: &GodotVersion) -> Result<PathBuf, Error> {
+        let udir = UserDir::new(self.io_adapter);
+        let engine_dir = udir.get_or_create_directory(Path::new(ENGINE_DIR))?;
+        let version_dir = engine_dir.join(version.to_string());
+        if self.io_adapter.path_exists(&version_dir) {
+            self.io_adapter.remove_dir_all(&version_dir)?;
+        }
+        self.io_adapter.create_dir(&version_dir)?;
+
+        let mut target = version_dir.join(GODOT_EXECUTABLE_NAME);
+        if binary.extension().is_some_and(|e| e == "exe") {
+            target.set_extension("exe");
+        }
+        self.io_adapter.copy_file(binary, &target)?;
+        self.io_adapter.set_executable(&target)?;
+
+        Ok(target)
+    }
+
+    fn install_templates(
+        &self,
+        binaries: &[&PathBuf],
+        version: &GodotVersion,
+    ) -> Result<PathBuf, Error> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_dir = gdir.get_specific_export_templates_directory(version)?;
+        if self.io_adapter.path_exists(&templates_dir) {
+            self.io_adapter.remove_dir_all(&templates_dir)?;
+        }
+        self.io_adapter.create_dir(&templates_dir)?;
+
+        for binary in binaries {
+            let name = get_file_name(binary);
+            let target_name = get_export_template_file_name(&name).unwrap_or_else(|| {
+                debug!("Unknown export template '{}', keeping its name", name);
+                name.clone()
+            });
+            self.io_adapter
+                .copy_file(binary, &templates_dir.join(target_name))?;
+        }
+
+        self.io_adapter.write_string_to_file(
+            &templates_dir.join(EXPORT_TEMPLATES_VERSION_FILE),
+            &version.get_export_template_name(),
+        )?;
+
+        Ok(templates_dir)
+    }
+}
+
+/// Get custom version kind from a build name.
+///
+/// The name can be given with or without the `custom.` prefix.
+pub fn get_build_kind(name: &str) -> Result<GodotVersionKind, BuildError> {
+    let name = name.strip_prefix("custom.").unwrap_or(name);
+    // Names must parse back to the same version
+    if name.split('.').any(|p| p.is_empty() || p == "mono") {
+        return Err(BuildError::InvalidName(name.into()));
+    }
+
+    Ok(GodotVersionKind::Custom(name.into()))
+}
+
+/// Get default build name for a git reference (e.g. `4.3-stable` gives `4-3-stable`).
+pub fn get_default_build_name(git_ref: &str) -> String {
+    slugify!(git_ref)
+}
+
+/// Parse engine version number from a `version.py` file (e.g. `4.3` or `4.2.2`).
+fn parse_version_info(contents: &str) -> Option<String> {
+    let get = |key: &str| {
+        contents.lines().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim() == key).then(|| v.trim().parse::<u32>().ok())?
+        })
+    };
+
+    let (major, minor) = (get("major")?, get("minor")?);
+    match get("patch") {
+        Some(patch) if patch > 0 => Some(format!("{}.{}.{}", major, minor, patch)),
+        _ => Some(format!("{}.{}", major, minor)),
+    }
+}
+
+fn get_editor_scons_args(version: &GodotVersion) -> &'static [&'static str] {
+    if version.major() >= 4 {
+        &["target=editor"]
+    } else {
+        &["tools=yes", "target=release_debug"]
+    }
+}
+
+fn get_templates_scons_args(version: &GodotVersion) -> [&'static [&'static str]; 2] {
+    if version.major() >= 4 {
+        [&["target=template_release"], &["target=template_debug"]]
+    } else {
+        [
+            &["tools=no", "target=release"],
+            &["tools=no", "target=release_debug"],
+        ]
+    }
+}
+
+fn get_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn is_debug_symbols(path: &Path) -> bool {
+    path.extension().is_some_and(|e| {
+        ["pdb", "exp", "lib", "a", "debugsymbols"].contains(&&*e.to_string_lossy())
+    })
+}
+
+fn is_editor_binary(path: &Path) -> bool {
+    let name = get_file_name(path);
+    name.contains(".editor.") || name.contains(".tools.")
+}
+
+/// Get the name Godot expects for a built export template (Godot 4, Linux and Windows only).
+///
+/// e.g. `godot.linuxbsd.template_release.x86_64` gives `linux_release.x86_64`.
+fn get_export_template_file_name(name: &str) -> Option<String> {
+    let parts: Vec<_> = name.split('.').collect();
+    let (platform, mode) = (parts.get(1)?, parts.get(2)?.strip_prefix("template_")?);
+
+    match *platform {
+        "linuxbsd" => Some(format!("linux_{}.{}", mode, parts.last()?)),
+        "windows" => {
+            let console = parts.contains(&"console");
+            let arch = parts
+                .iter()
+                .rev()
+                .find(|p| !["exe", "console"].contains(p))?;
+            Some(format!(
+                "windows_{}_{}{}.exe",
+                mode,
+                arch,
+                if console { "_console" } else { "" }
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use gdpm_io::{DefaultIoAdapter, FileLock, IoAdapter, MockIoAdapter};
+    use gdpm_types::version::GodotVersion;
+    use mockall::{predicate, Sequence};
+
+    use super::{
+        get_build_kind, get_export_template_file_name, parse_version_info, BuildHandler,
+        BuildOptions,
+    };
+
+    #[test]
+    fn test_parse_version_info() {
+        let info = indoc::indoc! {r#"
+            short_name = "godot"
+            name = "Godot Engine"
+            major = 4
+            minor = 3
+            patch = 0
+            status = "stable"
+        "#};
+        assert_eq!(parse_version_info(info), Some("4.3".into()));
+        assert_eq!(
+            parse_version_info(&info.replace("patch = 0", "patch = 2")),
+            Some("4.3.2".into())
+        );
+        assert_eq!(parse_version_info("major = 4"), None);
+    }
+
+    #[test]
+    fn test_get_export_template_file_name() {
+        let name = get_export_template_file_name;
+        assert_eq!(
+            name("godot.linuxbsd.template_release.x86_64"),
+            Some("linux_release.x86_64".into())
+        );
+        assert_eq!(
+            name("godot.windows.template_debug.x86_64.exe"),
+            Some("windows_debug_x86_64.exe".into())
+        );
+        assert_eq!(
+            name("godot.windows.template_debug.x86_64.console.exe"),
+            Some("windows_debug_x86_64_console.exe".into())
+        );
+        assert_eq!(name("godot.macos.template_release.arm64"), None);
+        assert_eq!(name("godot.x11.opt.64"), None);
+    }
+
+    #[test]
+    fn test_build() {
+        // Folder listings cannot be mocked, only the built binaries are created
+        let root = tempfile::tempdir().unwrap();
+        let config_dir = root.path().to_owned();
+        let gdpm_dir = root.path().join("gdpm");
+        let source_dir = gdpm_dir.join("sources").join("https-example-com-godot-git");
+        let bin_dir = source_dir.join("bin");
+        let engine_dir = gdpm_dir.join("engines").join("4.3.custom.mybranch");
+        let templates_dir = root
+            .path()
+            .join("Godot")
+            .join("export_templates")
+            .join("4.3.custom.mybranch");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        for target in ["editor", "template_release", "template_debug"] {
+            std::fs::write(
+                bin_dir.join(format!("godot.linuxbsd.{}.x86_64", target)),
+                "",
+            )
+            .unwrap();
+        }
+
+        let mut adapter = MockIoAdapter::new();
+        let mut seq = Sequence::new();
+        adapter
+            .expect_get_user_configuration_directory()
+            .returning(move || Ok(config_dir.clone()));
+        adapter.expect_canonicalize().returning(|p| p.to_owned());
+        adapter
+            .expect_path_exists()
+            .with(predicate::in_iter([
+                gdpm_dir.clone(),
+                gdpm_dir.join("sources"),
+                gdpm_dir.join("engines"),
+                gdpm_dir.join("gdpm.cfg"),
+                root.path().join("Godot"),
+                root.path().join("Godot").join("export_templates"),
+            ]))
+            .returning(|_| true);
+
+        // Checkout
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(source_dir.clone()))
+            .times(1)
+            .returning(|_| false);
+        let expect_command = |adapter: &mut MockIoAdapter,
+                              seq: &mut Sequence,
+                              program: &'static str,
+                              args: &[&str],
+                              dir: &Path| {
+            adapter
+                .expect_run_command()
+                .with(
+                    predicate::eq(program),
+                    predicate::eq(args.iter().map(|a| a.to_string()).collect::<Vec<_>>()),
+                    predicate::eq(dir.to_owned()),
+                )
+                .times(1)
+                .in_sequence(seq)
+                .returning(|_, _, _| Ok(()));
+        };
+        let url = "https://example.com/godot.git";
+        let source = source_dir.to_string_lossy().to_string();
+        expect_command(
+            &mut adapter,
+            &mut seq,
+            "git",
+            &["clone", url, &source],
+            &gdpm_dir.join("sources"),
+        );
+        expect_command(
+            &mut adapter,
+            &mut seq,
+            "git",
+            &["fetch", url, "my-branch"],
+            &source_dir,
+        );
+        expect_command(
+            &mut adapter,
+            &mut seq,
+            "git",
+            &["checkout", "--force", "--detach", "FETCH_HEAD"],
+            &source_dir,
+        );
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(source_dir.join("version.py")))
+            .times(1)
+            .returning(|_| Ok("major = 4\nminor = 3\npatch = 0\n".into()));
+
+        // Build, from an empty bin folder
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(bin_dir.clone()))
+            .times(1)
+            .returning(|_| true);
+        adapter
+            .expect_remove_dir_all()
+            .with(predicate::eq(bin_dir.clone()))
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(()));
+        for target in ["editor", "template_release", "template_debug"] {
+            expect_command(
+                &mut adapter,
+                &mut seq,
+                "scons",
+                &[&format!("target={}", target), "production=yes"],
+                &source_dir,
+            );
+        }
+        adapter
+            .expect_read_dir()
+            .with(predicate::eq(bin_dir.clone()))
+            .times(1)
+            .returning(|p| DefaultIoAdapter.read_dir(p));
+        let bin_dir_files = bin_dir.clone();
+        adapter
+            .expect_path_is_file()
+            .with(predicate::function(move |p: &Path| {
+                p.starts_with(&bin_dir_files)
+            }))
+            .returning(|_| true);
+
+        // Installation
+        let engine_path = engine_dir.join("godot");
+        adapter
+            .expect_path_exists()
+            .with(predicate::in_iter([
+                engine_dir.clone(),
+                templates_dir.clone(),
+            ]))
+            .times(2)
+            .returning(|_| false);
+        adapter
+            .expect_create_dir()
+            .with(predicate::in_iter([
+                engine_dir.clone(),
+                templates_dir.clone(),
+            ]))
+            .times(2)
+            .returning(|_| Ok(()));
+        for (binary, target) in [
+            ("editor", engine_path.clone()),
+            (
+                "template_release",
+                templates_dir.join("linux_release.x86_64"),
+            ),
+            ("template_debug", templates_dir.join("linux_debug.x86_64")),
+        ] {
+            adapter
+                .expect_copy_file()
+                .with(
+                    predicate::eq(bin_dir.join(format!("godot.linuxbsd.{}.x86_64", binary))),
+                    predicate::eq(target),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+        }
+        adapter
+            .expect_set_executable()
+            .with(predicate::eq(engine_path.clone()))
+            .times(1)
+            .returning(|_| Ok(()));
+        adapter
+            .expect_write_string_to_file()
+            .with(
+                predicate::eq(templates_dir.join("version.txt")),
+                predicate::eq("4.3.custom.mybranch"),
+            )
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // Registration
+        adapter
+            .expect_path_is_file()
+            .with(predicate::eq(engine_path.clone()))
+            .returning(|_| true);
+        adapter
+            .expect_run_command_with_output()
+            .with(
+                predicate::eq(engine_path.clone()),
+                predicate::eq(vec!["--version".to_string()]),
+                predicate::always(),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok("4.3.stable.custom_build.abcdef\n".into()));
+        adapter
+            .expect_lock_file()
+            .times(1)
+            .returning(|_| Ok(FileLock::default()));
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(gdpm_dir.join("gdpm.cfg")))
+            .returning(|_| Ok("".into()));
+        adapter
+            .expect_write_string_to_file()
+            .with(
+                predicate::eq(gdpm_dir.join("gdpm.cfg")),
+                predicate::function(|c: &str| {
+                    // Only the build hash is kept
+                    c.contains("4.3.custom.mybranch")
+                        && c.contains("abcdef")
+                        && !c.contains("4.3.stable")
+                }),
+            )
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let bhandler = BuildHandler::new(&adapter);
+        assert_eq!(bhandler.checkout(url, "my-branch").unwrap(), source_dir);
+        let kind = get_build_kind("custom.mybranch").unwrap();
+        let version = bhandler.get_build_version(&source_dir, kind).unwrap();
+        assert_eq!(
+            version,
+            GodotVersion::try_from("4.3.custom.mybranch").unwrap()
+        );
+
+        let output = bhandler
+            .build(
+                &source_dir,
+                &version,
+                &BuildOptions {
+                    scons_args: vec!["production=yes".into()],
+                    templates: true,
+                },
+            )
+            .unwrap();
+        assert_eq!(output.engine.path, engine_path);
+        assert_eq!(output.engine.hash, Some("abcdef".into()));
+        assert_eq!(output.templates_path, Some(templates_dir));
+    }
+}
@@ -2,9 +2,15 @@
 
 use std::path::{Path, PathBuf};
 
-use gdpm_io::{Error, IoAdapter};
-use gdpm_types::version::GodotVersion;
-use gdsettings_parser::{parse_gdsettings_file, GdSettings};
+use gdpm_downloader::{
+    config::DownloadConfig,
+    download::{Downloader, OFFICIAL_MIRROR_URL},
+    error::DownloadError,
+};
+use gdpm_io::{Error, FileLock, IoAdapter};
+use gdpm_types::version::{GodotVersion, GodotVersionReq};
+use gdsettings_parser::{parse_gdsettings_file, GdSettings, GdValue};
+use sha2::{Digest, Sha256};
 
 use crate::error::{ConfigError, ProjectError};
 
@@ -16,6 +22,18 @@ pub const GLOBAL_CONFIG_FILENAME: &str = "gdpm.cfg";
 pub const ENGINES_SECTION: &str = "engines";
 /// Project config filename.
 pub const PROJECT_CONFIG_FILENAME: &str = "project.godot";
+/// Export presets filename.
+pub const EXPORT_PRESETS_FILENAME: &str = "export_presets.cfg";
+/// Mirrors section name.
+pub const MIRRORS_SECTION: &str = "mirrors";
+/// Projects section name.
+pub const PROJECTS_SECTION: &str = "projects";
+/// Engine aliases section name.
+pub const ALIASES_SECTION: &str = "aliases";
+/// Downloads folder name, in the global directory.
+pub const DOWNLOADS_DIR: &str = "downloads";
+/// Lock files folder name, in the global directory.
+pub const LOCKS_DIR: &str = "locks";
 
 /// Godot directory handler.
 pub struct GodotDir<'a, I: IoAdapter> {
@@ -107,11 +125,45 @@ impl<'a, I: IoAdapter> UserDir<'a, I> {
         Ok(path)
     }
 
+    /// Get path for a downloaded file, in the downloads directory.
+    pub fn get_download_path(&self, name: &str) -> Result<PathBuf, Error> {
+        Ok(self
+            .get_or_create_directory(Path::new(DOWNLOADS_DIR))?
+            .join(name))
+    }
+
     /// Get file in global directory.
     pub fn get_file(&self, path: &Path) -> Result<PathBuf, Error> {
         Ok(self.get_or_create_global_directory()?.join(path))
     }
 
+    /// Get lock file path for a file, in the locks directory.
+    ///
+    /// Lock files are not stored next to the locked file, to keep project folders clean.
+    /// They are named from a stable hash of the canonical path, so that every gdpm version
+    /// and every path to the same file (e.g. through symbolic links) share the same lock.
+    pub fn get_lock_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        let canonical_path = self.io_adapter.canonicalize(path);
+        let digest = Sha256::digest(canonical_path.as_os_str().as_encoded_bytes());
+        let mut hash = [0; 8];
+        hash.copy_from_slice(&digest[..8]);
+
+        let file_name = canonical_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.get_file(&Path::new(LOCKS_DIR).join(format!(
+            "{}-{:016x}.lock",
+            file_name,
+            u64::from_be_bytes(hash)
+        )))
+    }
+
+    /// Take an exclusive lock on a file, waiting for other gdpm processes to release it.
+    pub fn lock_file(&self, path: &Path) -> Result<FileLock, Error> {
+        self.io_adapter.lock_file(&self.get_lock_path(path)?)
+    }
+
     /// Read file to string from global directory.
     pub fn read_file_to_string(&self, path: &Path) -> Result<String, Error> {
         self.io_adapter.read_file_to_string(&self.get_file(path)?)
@@ -146,18 +198,138 @@ impl<'a, I: IoAdapter> GlobalConfig<'a, I> {
         let udir = UserDir::new(self.io_adapter);
 
         let path = self.get_global_config_path();
-        udir.get_or_create_file(path)?;
+        let full_path = udir.get_or_create_file(path)?;
 
         let contents = udir.read_file_to_string(path)?;
-        parse_gdsettings_file(&contents).map_err(Into::into)
+        parse_gdsettings_file(&contents)
+            .map_err(|e| ConfigError::MalformedSettings(e.with_path(&full_path)))
+    }
+
+    /// Load download configuration from global configuration.
+    pub fn load_download_config(&self) -> Result<DownloadConfig, ConfigError> {
+        DownloadConfig::from_settings(&self.load()?).map_err(Into::into)
+    }
+
+    /// Get download mirror URLs from global configuration, in order.
+    ///
+    /// The official mirror is always tried last.
+    pub fn get_mirrors(&self) -> Result<Vec<String>, ConfigError> {
+        let settings = self.load()?;
+        let mut mirrors = vec![];
+
+        if let Some(value) = settings.get_property(MIRRORS_SECTION, "urls") {
+            let urls = value
+                .to_array()
+                .and_then(|a| a.iter().map(|v| v.to_str()).collect::<Option<Vec<_>>>())
+                .ok_or_else(|| {
+                    DownloadError::InvalidConfig("mirrors/urls".into(), "an array of URLs".into())
+                })?;
+
+            for url in urls {
+                mirrors.push(Downloader::parse_mirror_url(&url)?);
+            }
+        }
+
+        if !mirrors.iter().any(|m| m == OFFICIAL_MIRROR_URL) {
+            mirrors.push(OFFICIAL_MIRROR_URL.into());
+        }
+
+        Ok(mirrors)
+    }
+
+    /// Get project root folders from global configuration.
+    pub fn get_project_roots(&self) -> Result<Vec<PathBuf>, ConfigError> {
+        let settings = self.load()?;
+
+        match settings.get_property(PROJECTS_SECTION, "roots") {
+            Some(value) => value
+                .to_array()
+                .and_then(|a| a.iter().map(|v| v.to_str()).collect::<Option<Vec<_>>>())
+                .map(|roots| roots.into_iter().map(PathBuf::from).collect())
+                .ok_or_else(|| {
+                    ConfigError::InvalidSetting("projects/roots".into(), "an array of paths".into())
+                }),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Get engine aliases from global configuration, sorted by name.
+    pub fn get_aliases(&self) -> Result<Vec<(String, GodotVersionReq)>, ConfigError> {
+        let settings = self.load()?;
+
+        settings
+            .get_section(ALIASES_SECTION)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| {
+                value
+                    .to_str()
+                    .and_then(|v| v.parse().ok())
+                    .map(|req| (name.clone(), req))
+                    .ok_or_else(|| {
+                        ConfigError::InvalidSetting(
+                            format!("{}/{}", ALIASES_SECTION, name),
+                            "an engine version or requirement".into(),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Get engine alias target from global configuration.
+    pub fn get_alias(&self, name: &str) -> Result<Option<GodotVersionReq>, ConfigError> {
+        Ok(self
+            .get_aliases()?
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, req)| req))
+    }
+
+    /// Set engine alias in global configuration.
+    pub fn set_alias(&self, name: &str, target: &GodotVersionReq) -> Result<(), ConfigError> {
+        self.update(|settings| {
+            settings.set_property(ALIASES_SECTION, name, GdValue::String(target.to_string()));
+            Ok(())
+        })
+    }
+
+    /// Remove engine alias from global configuration.
+    ///
+    /// Returns `false` if the alias did not exist.
+    pub fn unset_alias(&self, name: &str) -> Result<bool, ConfigError> {
+        self.update(|settings| Ok(settings.remove_property(ALIASES_SECTION, name).is_ok()))
     }
 
     /// Save global configuration.
+    ///
+    /// Use [`GlobalConfig::update`] to change a loaded configuration.
     pub fn save(&self, settings: GdSettings) -> Result<(), ConfigError> {
         let udir = UserDir::new(self.io_adapter);
         udir.write_string_to_file(self.get_global_config_path(), &settings.to_string())
             .map_err(Into::into)
     }
+
+    /// Load, change and save global configuration, while holding a lock on it so that
+    /// concurrent gdpm processes do not overwrite each other's changes.
+    ///
+    /// The configuration is not saved if `f` fails. `f` must not update the global
+    /// configuration itself, as it would wait for the lock forever.
+    pub fn update<T, E: From<ConfigError>>(
+        &self,
+        f: impl FnOnce(&mut GdSettings) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let udir = UserDir::new(self.io_adapter);
+        let path = udir
+            .get_file(self.get_global_config_path())
+            .map_err(ConfigError::from)?;
+        let _lock = udir.lock_file(&path).map_err(ConfigError::from)?;
+
+        let mut settings = self.load()?;
+        let value = f(&mut settings)?;
+        self.save(settings)?;
+
+        Ok(value)
+    }
 }
 
 /// Project configuration handler.
@@ -192,7 +364,8 @@ impl<'a, I: IoAdapter> ProjectConfig<'a, I> {
     pub fn load(&self, path: &Path) -> Result<GdSettings, ProjectError> {
         let project = self.ensure_project_exists(path)?;
         let contents = self.io_adapter.read_file_to_string(&project)?;
-        parse_gdsettings_file(&contents).map_err(ProjectError::MalformedProject)
+        parse_gdsettings_file(&contents)
+            .map_err(|e| ProjectError::MalformedProject(e.with_path(&project)))
     }
 
     /// Save project configuration.
@@ -203,6 +376,89 @@ impl<'a, I: IoAdapter> ProjectConfig<'a, I> {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    /// Load, change and save project configuration, while holding a lock on it.
+    ///
+    /// The configuration is not saved if `f` fails.
+    pub fn update<T, E: From<ProjectError>>(
+        &self,
+        path: &Path,
+        f: impl FnOnce(&mut GdSettings) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let project = self.ensure_project_exists(path)?;
+        let _lock = UserDir::new(self.io_adapter)
+            .lock_file(&project)
+            .map_err(ProjectError::from)?;
+
+        let mut settings = self.load(path)?;
+        let value = f(&mut settings)?;
+        self.save(path, settings)?;
+
+        Ok(value)
+    }
+}
+
+/// Export presets configuration handler.
+pub struct ExportPresetsConfig<'a, I: IoAdapter> {
+    io_adapter: &'a I,
+}
+
+impl<'a, I: IoAdapter> ExportPresetsConfig<'a, I> {
+    /// Creates a new export presets config.
+    pub fn new(io_adapter: &'a I) -> Self {
+        Self { io_adapter }
+    }
+
+    /// Get export presets path.
+    pub fn get_export_presets_path(&self, path: &Path) -> PathBuf {
+        path.join(EXPORT_PRESETS_FILENAME)
+    }
+
+    /// Check if export presets exist.
+    pub fn exists(&self, path: &Path) -> bool {
+        self.io_adapter
+            .path_exists(&self.get_export_presets_path(path))
+    }
+
+    /// Load export presets.
+    pub fn load(&self, path: &Path) -> Result<GdSettings, ProjectError> {
+        let presets = self.get_export_presets_path(path);
+        if !self.io_adapter.path_exists(&presets) {
+            return Err(ProjectError::ExportPresetsNotFound(
+                presets.to_string_lossy().to_string(),
+            ));
+        }
+
+        let contents = self.io_adapter.read_file_to_string(&presets)?;
+        parse_gdsettings_file(&contents)
+            .map_err(|e| ProjectError::MalformedProject(e.with_path(&presets)))
+    }
+
+    /// Save export presets.
+    pub fn save(&self, path: &Path, settings: GdSettings) -> Result<(), ProjectError> {
+        self.io_adapter
+            .write_string_to_file(&self.get_export_presets_path(path), &settings.to_string())
+            .map_err(Into::into)
+    }
+
+    /// Load, change and save export presets, while holding a lock on them.
+    ///
+    /// The presets are not saved if `f` fails.
+    pub fn update<T, E: From<ProjectError>>(
+        &self,
+        path: &Path,
+        f: impl FnOnce(&mut GdSettings) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let _lock = UserDir::new(self.io_adapter)
+            .lock_file(&self.get_export_presets_path(path))
+            .map_err(ProjectError::from)?;
+
+        let mut settings = self.load(path)?;
+        let value = f(&mut settings)?;
+        self.save(path, settings)?;
+
+        Ok(value)
+    }
 }
 
 #[cfg(test)]
@@ -383,16 +639,64 @@ mod tests {
             udir.write_string_to_file(&PathBuf::from("foo"), "OK")
                 .unwrap();
         }
+
+        #[test]
+        fn test_get_lock_path() {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(Path::new("/home/user/.config/gdpm")))
+                .returning(|_| true);
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+
+            let udir = UserDir::new(&adapter);
+            assert_eq!(
+                udir.get_lock_path(Path::new("/nonexistent/game/project.godot"))
+                    .unwrap(),
+                PathBuf::from("/home/user/.config/gdpm/locks/project.godot-ed63d00acad3247f.lock")
+            );
+        }
+
+        #[test]
+        fn test_get_lock_path_symlink() {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter.expect_path_exists().returning(|_| true);
+            adapter
+                .expect_canonicalize()
+                .with(predicate::eq(Path::new("/home/user/link.godot")))
+                .return_const(PathBuf::from("/home/user/game/project.godot"));
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+
+            // Paths to the same file share their lock
+            let udir = UserDir::new(&adapter);
+            assert_eq!(
+                udir.get_lock_path(Path::new("/home/user/link.godot"))
+                    .unwrap(),
+                udir.get_lock_path(Path::new("/home/user/game/project.godot"))
+                    .unwrap()
+            );
+        }
     }
 
     mod globalconfig {
         use mockall::predicate;
         use std::path::{Path, PathBuf};
 
-        use gdpm_io::MockIoAdapter;
-        use gdsettings_parser::{GdSettings, GdSettingsType};
+        use gdpm_downloader::download::OFFICIAL_MIRROR_URL;
+        use gdpm_io::{FileLock, MockIoAdapter};
+        use gdsettings_parser::{GdSettings, GdSettingsType, GdValue};
+        use indoc::indoc;
 
-        use crate::config::{GlobalConfig, GLOBAL_CONFIG_FILENAME};
+        use crate::{
+            config::{GlobalConfig, GLOBAL_CONFIG_FILENAME},
+            error::ConfigError,
+        };
 
         #[test]
         fn test_get_global_config_path() {
@@ -444,6 +748,106 @@ mod tests {
             assert_eq!(gconf.load().unwrap(), empty_settings);
         }
 
+        #[test]
+        fn test_get_project_roots() {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter.expect_path_exists().returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(Path::new("/home/user/.config/gdpm/gdpm.cfg")))
+                .returning(|_| {
+                    Ok(indoc! {r#"
+                        [projects]
+
+                        roots=["/home/user/games", "/opt/jams"]
+                    "#}
+                    .into())
+                });
+
+            let gconf = GlobalConfig::new(&adapter);
+            assert_eq!(
+                gconf.get_project_roots().unwrap(),
+                vec![
+                    PathBuf::from("/home/user/games"),
+                    PathBuf::from("/opt/jams")
+                ]
+            );
+        }
+
+        #[test]
+        fn test_get_aliases() {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter.expect_path_exists().returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(Path::new("/home/user/.config/gdpm/gdpm.cfg")))
+                .returning(|_| {
+                    Ok(indoc! {r#"
+                        [aliases]
+
+                        team="4.3.mono"
+                        lts="4.2.2"
+                        next="beta"
+                    "#}
+                    .into())
+                });
+
+            let gconf = GlobalConfig::new(&adapter);
+            let aliases: Vec<_> = gconf
+                .get_aliases()
+                .unwrap()
+                .into_iter()
+                .map(|(n, r)| format!("{}={}", n, r))
+                .collect();
+            assert_eq!(aliases, vec!["lts=4.2.2", "next=beta", "team=4.3.mono"]);
+            assert_eq!(
+                gconf.get_alias("lts").unwrap().unwrap().to_string(),
+                "4.2.2"
+            );
+            assert_eq!(gconf.get_alias("unknown").unwrap(), None);
+        }
+
+        #[test]
+        fn test_get_mirrors() {
+            let mut adapter = MockIoAdapter::new();
+
+            adapter
+                .expect_get_user_configuration_directory()
+                .times(2)
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+
+            adapter.expect_path_exists().times(3).returning(|_| true);
+
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(Path::new("/home/user/.config/gdpm/gdpm.cfg")))
+                .times(1)
+                .returning(|_| {
+                    Ok(indoc! {r#"
+                        [mirrors]
+
+                        urls=["https://mirror.local/godot", "https://mirror2.local/godot/"]
+                    "#}
+                    .into())
+                });
+
+            let gconf = GlobalConfig::new(&adapter);
+            assert_eq!(
+                gconf.get_mirrors().unwrap(),
+                vec![
+                    "https://mirror.local/godot/".to_string(),
+                    "https://mirror2.local/godot/".to_string(),
+                    OFFICIAL_MIRROR_URL.to_string()
+                ]
+            );
+        }
+
         #[test]
         fn test_save() {
             let mut adapter = MockIoAdapter::new();
@@ -473,6 +877,52 @@ mod tests {
             let gconf = GlobalConfig::new(&adapter);
             gconf.save(empty_settings).unwrap();
         }
+
+        #[test]
+        fn test_update() {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter.expect_path_exists().returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .returning(|_| Ok("default_engine=\"4.2\"\n".into()));
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+            adapter
+                .expect_lock_file()
+                .withf(|path| {
+                    path.starts_with("/home/user/.config/gdpm/locks")
+                        && path
+                            .file_name()
+                            .unwrap()
+                            .to_string_lossy()
+                            .starts_with("gdpm.cfg-")
+                })
+                .times(2)
+                .returning(|_| Ok(FileLock::default()));
+            adapter
+                .expect_write_string_to_file()
+                .withf(|path, contents| {
+                    path == Path::new("/home/user/.config/gdpm/gdpm.cfg")
+                        && contents.contains("default_engine = \"4.3\"")
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let gconf = GlobalConfig::new(&adapter);
+            gconf
+                .update(|settings| {
+                    settings.set_property("", "default_engine", GdValue::String("4.3".into()));
+                    Ok::<_, ConfigError>(())
+                })
+                .unwrap();
+
+            // Nothing is saved on failure
+            assert!(gconf
+                .update(|_| Err::<(), _>(ConfigError::InvalidSetting("a".into(), "b".into())))
+                .is_err());
+        }
     }
 
     mod projectconfig {
@@ -482,7 +932,10 @@ mod tests {
         use gdpm_io::MockIoAdapter;
         use gdsettings_parser::{GdSettings, GdSettingsType};
 
-        use crate::config::{ProjectConfig, PROJECT_CONFIG_FILENAME};
+        use crate::{
+            config::{ProjectConfig, PROJECT_CONFIG_FILENAME},
+            error::ProjectError,
+        };
 
         #[test]
         fn test_get_project_config_path() {
@@ -534,6 +987,36 @@ mod tests {
             assert_eq!(pconf.load(Path::new("/")).unwrap(), empty_settings);
         }
 
+        #[test]
+        fn test_load_malformed() {
+            let mut adapter = MockIoAdapter::new();
+
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(Path::new("/project.godot")))
+                .times(1)
+                .returning(|_| true);
+
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(Path::new("/project.godot")))
+                .times(1)
+                .returning(|_| Ok("[application]\n<<<<<<< HEAD\n".into()));
+
+            let pconf = ProjectConfig::new(&adapter);
+            match pconf.load(Path::new("/")).unwrap_err() {
+                ProjectError::MalformedProject(e) => {
+                    let diagnostic = e.diagnostic().unwrap();
+                    assert_eq!(
+                        diagnostic.path.as_deref(),
+                        Some(Path::new("/project.godot"))
+                    );
+                    assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+                }
+                e => panic!("unexpected error: {e:?}"),
+            }
+        }
+
         #[test]
         fn test_save() {
             let mut adapter = MockIoAdapter::new();
@@ -1,26 +1,34 @@
 //! Engine module.
 
 use std::{
+    ffi::OsString,
     path::{Path, PathBuf},
     process::Command,
     str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use colored::Colorize;
 use gdpm_io::{Error, IoAdapter};
-use gdpm_types::version::{GodotVersion, SystemVersion};
+use gdpm_types::version::{
+    GodotBuildInfo, GodotVersion, GodotVersionKind, GodotVersionReq, SystemVersion,
+};
 use gdsettings_parser::{
     parse_gdsettings_file, GdSettings, GdSettingsMap, GdSettingsType, GdValue,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     config::{GlobalConfig, GodotDir, UserDir, ENGINES_SECTION},
-    error::{ConfigError, EngineError},
+    error::EngineError,
 };
 
-const ENGINE_DIR: &str = "engines";
+pub(crate) const ENGINE_DIR: &str = "engines";
 const GODOT_EXECUTABLE_NAME: &str = "godot";
+const REMOTE_CACHE_FILE: &str = "remote-cache.cfg";
+/// Maximum time for an engine executable to report its version.
+const VERSION_DETECTION_TIMEOUT: Duration = Duration::from_secs(10);
+pub(crate) const EXPORT_TEMPLATES_VERSION_FILE: &str = "version.txt";
 
 /// Engine info
 #[derive(Debug, PartialEq, Clone)]
@@ -29,6 +37,28 @@ pub struct EngineInfo {
     pub version: GodotVersion,
     /// Path to engine
     pub path: PathBuf,
+    /// Build commit hash, detected from the engine executable
+    pub hash: Option<String>,
+    /// Last time the engine was used, as a UNIX timestamp
+    pub last_used: Option<u64>,
+}
+
+/// Engine disk usage, in bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineDiskUsage {
+    /// Engine info
+    pub engine: EngineInfo,
+    /// Size of the engine installation folder, if installed by gdpm
+    pub engine_size: Option<u64>,
+    /// Size of the export templates folder, if installed
+    pub templates_size: Option<u64>,
+}
+
+impl EngineDiskUsage {
+    /// Get total size
+    pub fn total(&self) -> u64 {
+        self.engine_size.unwrap_or(0) + self.templates_size.unwrap_or(0)
+    }
 }
 
 impl EngineInfo {
@@ -41,22 +71,31 @@ impl EngineInfo {
         if !io_adapter.path_is_file(&path) {
             Err(EngineError::EngineMissingFromPath(version, path))
         } else {
-            Ok(Self { version, path })
+            Ok(Self {
+                version,
+                path,
+                hash: None,
+                last_used: None,
+            })
         }
     }
 
-    /// Extract engine info from settings
+    /// Extract engine info from settings.
+    ///
+    /// Invalid entries are skipped, see [`EngineHandler::check`] to find them.
     pub fn from_settings(settings: GdSettings) -> Vec<Self> {
         let mut engines = vec![];
         let properties = settings.get_section(ENGINES_SECTION);
         if let Some(props) = properties {
-            for (_, value) in props.into_iter() {
-                if let Some(e) = EngineInfo::from_gdvalue(value) {
-                    engines.push(e);
+            for (key, value) in props.into_iter() {
+                match EngineInfo::from_gdvalue(value) {
+                    Ok(e) => engines.push(e),
+                    Err(e) => debug!("Skipping engine entry '{}': {}", key, e),
                 }
             }
         }
 
+        engines.sort_by(|a, b| a.version.cmp(&b.version));
         engines
     }
 
@@ -72,38 +111,79 @@ impl EngineInfo {
 
     /// To GdValue
     pub fn to_gdvalue(&self) -> GdValue {
-        GdValue::Object(vec![
+        let mut values = vec![
             (
                 "path".into(),
                 GdValue::String(self.path.to_string_lossy().to_string()),
             ),
             ("version".into(), GdValue::String(self.version.to_string())),
-        ])
+        ];
+
+        if let Some(hash) = &self.hash {
+            values.push(("hash".into(), GdValue::String(hash.clone())));
+        }
+        if let Some(last_used) = self.last_used.and_then(|t| i64::try_from(t).ok()) {
+            values.push(("last_used".into(), GdValue::Int(last_used)));
+        }
+
+        GdValue::Object(values)
     }
 
-    /// From gdvalue.
-    pub fn from_gdvalue(value: GdValue) -> Option<Self> {
-        if let Some(map) = value.to_object() {
-            let path = PathBuf::from(
-                map.get("path")
-                    .and_then(|x| x.to_str())
-                    .unwrap_or_else(|| String::from("unknown")),
-            );
-            let version = map
-                .get("version")
-                .and_then(|x| x.to_str())
-                .unwrap_or_else(|| String::from("unknown"));
+    /// Check if a detected engine build matches this entry.
+    ///
+    /// The build hash is only compared when known.
+    /// Custom builds are registered under their own name, so only their version number is compared.
+    pub fn matches_build(&self, build: &GodotBuildInfo) -> bool {
+        let same_hash = match (&self.hash, &build.hash) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        let same_version = match (self.version.kind(), build.version.kind()) {
+            (GodotVersionKind::Custom(_), GodotVersionKind::Custom(_)) => {
+                self.version.version() == build.version.version()
+                    && self.version.mono() == build.version.mono()
+            }
+            _ => self.version == build.version,
+        };
 
-            let version = GodotVersion::from_str(&version).expect("unknown version");
-            Some(Self { path, version })
-        } else {
-            None
-        }
+        same_version && same_hash
+    }
+
+    /// From gdvalue.
+    pub fn from_gdvalue(value: GdValue) -> Result<Self, EngineError> {
+        let map = value
+            .to_object()
+            .ok_or_else(|| EngineError::InvalidEngineEntry("expected an object".into()))?;
+        let path = map
+            .get("path")
+            .and_then(|x| x.to_str())
+            .ok_or_else(|| EngineError::InvalidEngineEntry("missing 'path' string".into()))?;
+        let version = map
+            .get("version")
+            .and_then(|x| x.to_str())
+            .ok_or_else(|| EngineError::InvalidEngineEntry("missing 'version' string".into()))?;
+        let version = GodotVersion::from_str(&version)
+            .map_err(|e| EngineError::InvalidEngineEntry(e.to_string()))?;
+
+        let hash = map.get("hash").and_then(|x| x.to_str());
+        // Older configurations store the timestamp as a string
+        let last_used = map.get("last_used").and_then(|x| {
+            x.to_i64()
+                .and_then(|t| u64::try_from(t).ok())
+                .or_else(|| x.to_str().and_then(|t| t.parse().ok()))
+        });
+
+        Ok(Self {
+            path: PathBuf::from(path),
+            version,
+            hash,
+            last_used,
+        })
     }
 
-    /// Check if version is version 4.
+    /// Check if version is version 4 or later.
     pub fn is_version_4(&self) -> bool {
-        self.version.to_string().starts_with("4.")
+        self.version.major() >= 4
     }
 
     /// Show
@@ -121,6 +201,76 @@ impl EngineInfo {
     }
 }
 
+/// Engine configuration issue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineConfigIssue {
+    /// Engine entry can not be read (key, reason)
+    InvalidEntry(String, String),
+    /// Engine executable is missing (key, path)
+    MissingExecutable(String, PathBuf),
+    /// Default engine is invalid or not registered
+    InvalidDefault(String),
+}
+
+impl std::fmt::Display for EngineConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEntry(key, reason) => {
+                write!(f, "Engine entry '{}' is invalid: {}", key, reason)
+            }
+            Self::MissingExecutable(key, path) => write!(
+                f,
+                "Engine entry '{}' points to a missing executable '{}'",
+                key,
+                path.display()
+            ),
+            Self::InvalidDefault(default) => write!(
+                f,
+                "Default engine '{}' is invalid or not registered",
+                default
+            ),
+        }
+    }
+}
+
+/// Engine executable found while scanning a folder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScannedEngine {
+    /// Engine version which is not registered yet
+    New(EngineInfo),
+    /// Engine already registered at this path
+    AlreadyRegistered(EngineInfo),
+    /// Engine version already registered or found at another path (path, existing entry)
+    Duplicate(PathBuf, EngineInfo),
+}
+
+/// Export templates issue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportTemplatesIssue {
+    /// Version file is missing (path)
+    MissingVersionFile(PathBuf),
+    /// Version file does not match the expected version (expected, found)
+    VersionMismatch(String, String),
+    /// No template file found
+    NoTemplates,
+}
+
+impl std::fmt::Display for ExportTemplatesIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingVersionFile(path) => {
+                write!(f, "Version file '{}' is missing", path.display())
+            }
+            Self::VersionMismatch(expected, found) => write!(
+                f,
+                "Version file reports '{}' instead of '{}'",
+                found, expected
+            ),
+            Self::NoTemplates => write!(f, "No template file found"),
+        }
+    }
+}
+
 /// Engine handler.
 pub struct EngineHandler<'a, I: IoAdapter> {
     io_adapter: &'a I,
@@ -139,42 +289,184 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         Ok(EngineInfo::from_settings(config))
     }
 
+    /// Get latest registered engine, preferring stable versions
+    pub fn get_latest(&self) -> Result<Option<EngineInfo>, EngineError> {
+        let engines = self.list()?;
+        let stable = engines
+            .iter()
+            .filter(|e| e.version.is_stable())
+            .max_by(|a, b| a.version.cmp(&b.version));
+
+        Ok(stable.or(engines.last()).cloned())
+    }
+
     /// Update multiple engines info.
     pub fn update_all(&self, entries: Vec<EngineInfo>) -> Result<(), EngineError> {
         let gconf = GlobalConfig::new(self.io_adapter);
-        let mut configuration = gconf.load()?;
-        for entry in entries {
-            configuration.set_property(ENGINES_SECTION, &entry.get_slug(), entry.to_gdvalue())
-        }
+        gconf.update(|configuration| {
+            for entry in entries {
+                configuration.set_property(ENGINES_SECTION, &entry.get_slug(), entry.to_gdvalue())
+            }
 
-        gconf.save(configuration).map_err(Into::into)
+            Ok(())
+        })
     }
 
     /// Register engine entry.
-    pub fn register(&self, entry: EngineInfo) -> Result<(), EngineError> {
-        let mut engine_list = self.list()?;
+    pub fn register(&self, mut entry: EngineInfo) -> Result<(), EngineError> {
+        entry.last_used.get_or_insert_with(unix_timestamp);
         let version = entry.version.clone();
-        if let Some(other_entry) = engine_list
-            .iter_mut()
-            .find(|x| x.get_slug() == entry.get_slug())
-        {
-            *other_entry = entry;
-        } else {
-            engine_list.push(entry);
-        }
 
         debug!(
             "Registering entry for version '{}' ...",
             version.to_string().color("green")
         );
-        self.update_all(engine_list)?;
 
-        // Check if default engine is not defined
-        if self.get_default()?.is_none() {
-            self.set_as_default(&version)?;
+        // Entries and default engine are read under the configuration lock, so that
+        // concurrent registrations are not lost
+        let gconf = GlobalConfig::new(self.io_adapter);
+        gconf.update(|configuration| {
+            let has_default = self.get_default()?.is_some() || self.is_default(&version)?;
+            configuration.set_property(ENGINES_SECTION, &entry.get_slug(), entry.to_gdvalue());
+
+            // Check if default engine is not defined
+            if !has_default {
+                configuration.set_property(
+                    "",
+                    "default_engine",
+                    GdValue::String(version.to_string()),
+                );
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Check if the default engine names a version, even if it is not registered yet.
+    fn is_default(&self, version: &GodotVersion) -> Result<bool, EngineError> {
+        let Some(e) = self.read_default_engine()? else {
+            return Ok(false);
+        };
+
+        Ok(match parse_alias_req(&e) {
+            Some(req) => self.expand_alias(&req).is_ok_and(|r| r.matches(version)),
+            None => GodotVersion::from_str(&e).is_ok_and(|v| &v == version),
+        })
+    }
+
+    /// Detect engine version from its executable, using `--version`.
+    pub fn detect_version(&self, path: &Path) -> Result<GodotBuildInfo, EngineError> {
+        let stdout = self.io_adapter.run_command_with_output(
+            path,
+            &["--version".into()],
+            VERSION_DETECTION_TIMEOUT,
+        )?;
+
+        // Extra lines can be printed before the version
+        stdout
+            .lines()
+            .rev()
+            .find_map(|l| GodotBuildInfo::from_str(l).ok())
+            .ok_or_else(|| {
+                let first_line = stdout.lines().next().unwrap_or_default().trim();
+                EngineError::VersionDetectionFailed(path.to_owned(), first_line.to_string())
+            })
+    }
+
+    /// Detect engine build from its executable and store its hash in the entry.
+    ///
+    /// Returns the detected build if its version disagrees with the entry.
+    pub fn update_build_info(
+        &self,
+        entry: &mut EngineInfo,
+    ) -> Result<Option<GodotBuildInfo>, EngineError> {
+        let build = self.detect_version(&entry.path)?;
+        entry.hash.clone_from(&build.hash);
+
+        Ok((build.version != entry.version).then_some(build))
+    }
+
+    /// Check that the engine executable matches the entry version and build hash.
+    ///
+    /// Returns the detected build if it disagrees with the entry.
+    pub fn check_build(&self, entry: &EngineInfo) -> Result<Option<GodotBuildInfo>, EngineError> {
+        let build = self.detect_version(&entry.path)?;
+        Ok((!entry.matches_build(&build)).then_some(build))
+    }
+
+    /// Find engine executable candidates in a directory, up to a max depth.
+    pub fn scan(&self, path: &Path, max_depth: usize) -> Result<Vec<PathBuf>, EngineError> {
+        let mut candidates = vec![];
+        let mut entries: Vec<_> = self
+            .io_adapter
+            .read_dir(path)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+
+        for entry in entries {
+            let name = entry
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            if self.io_adapter.path_is_file(&entry) {
+                if is_engine_candidate(&name) {
+                    candidates.push(entry);
+                }
+            } else if max_depth > 0 && !name.starts_with('.') && name != "GodotSharp" {
+                match self.scan(&entry, max_depth - 1) {
+                    Ok(c) => candidates.extend(c),
+                    Err(e) => debug!("Skipping folder '{}': {}", entry.display(), e),
+                }
+            }
         }
 
-        Ok(())
+        Ok(candidates)
+    }
+
+    /// Find engine executables in a directory up to a max depth, and detect their version.
+    ///
+    /// Executables whose version can not be detected are skipped, and the first executable
+    /// found for a version is kept when it is not already registered.
+    pub fn scan_engines(
+        &self,
+        path: &Path,
+        max_depth: usize,
+    ) -> Result<Vec<ScannedEngine>, EngineError> {
+        let mut scanned: Vec<ScannedEngine> = vec![];
+
+        for path in self.scan(path, max_depth)? {
+            let build = match self.detect_version(&path) {
+                Ok(b) => b,
+                Err(e) => {
+                    debug!("Skipping '{}': {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let found = scanned.iter().find_map(|s| match s {
+                ScannedEngine::New(e) if e.version == build.version => Some(e.clone()),
+                _ => None,
+            });
+            let existing = match found {
+                Some(e) => Some(e),
+                None => self.has_version(&build.version)?,
+            };
+
+            scanned.push(match existing {
+                Some(e) if e.path == path => ScannedEngine::AlreadyRegistered(e),
+                Some(e) => ScannedEngine::Duplicate(path, e),
+                None => {
+                    let mut engine_info = EngineInfo::new(self.io_adapter, build.version, path)?;
+                    engine_info.hash = build.hash;
+                    ScannedEngine::New(engine_info)
+                }
+            });
+        }
+
+        Ok(scanned)
     }
 
     /// Unregister engine entry.
@@ -197,9 +489,10 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
             version.to_string().color("green")
         );
         let gconf = GlobalConfig::new(self.io_adapter);
-        let mut conf = gconf.load()?;
-        conf.remove_property(ENGINES_SECTION, &version.slug())?;
-        gconf.save(conf).map_err(Into::into)
+        gconf.update(|conf| {
+            conf.remove_property(ENGINES_SECTION, &version.slug())?;
+            Ok(())
+        })
     }
 
     /// Get engine version.
@@ -212,6 +505,20 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         }
     }
 
+    /// Resolve the highest installed engine matching a version requirement.
+    pub fn resolve(&self, req: &GodotVersionReq) -> Result<EngineInfo, EngineError> {
+        let req = &self.expand_alias(req)?;
+        if let Some(version) = req.exact() {
+            return self.get_version(version);
+        }
+
+        let engine_list = self.list()?;
+        let version = req
+            .resolve(engine_list.iter().map(|e| &e.version))
+            .ok_or_else(|| EngineError::NoMatchingEngine(req.clone()))?;
+        self.get_version(&version)
+    }
+
     /// Has version.
     pub fn has_version(&self, version: &GodotVersion) -> Result<Option<EngineInfo>, EngineError> {
         match self.get_version(version) {
@@ -229,7 +536,7 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         version: &GodotVersion,
         path: &Path,
     ) -> Result<(), EngineError> {
-        let engine = self.get_version(version)?;
+        let engine = self.mark_as_used(version)?;
         Command::new(engine.path)
             .arg("--path")
             .arg(path)
@@ -246,7 +553,7 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         version: &GodotVersion,
         path: &Path,
     ) -> Result<(), EngineError> {
-        let engine = self.get_version(version)?;
+        let engine = self.mark_as_used(version)?;
         Command::new(engine.path)
             .arg("--path")
             .arg(path)
@@ -263,7 +570,7 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         args: &[String],
         path: &Path,
     ) -> Result<(), EngineError> {
-        let engine = self.get_version(version)?;
+        let engine = self.mark_as_used(version)?;
         Command::new(engine.path)
             .arg("--path")
             .arg(path)
@@ -274,6 +581,130 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         Ok(())
     }
 
+    /// Execute engine version with raw arguments, returning its exit code.
+    ///
+    /// On Unix systems, the current process is replaced by the engine.
+    pub fn exec_version(
+        &self,
+        version: &GodotVersion,
+        args: &[OsString],
+    ) -> Result<i32, EngineError> {
+        let engine = self.mark_as_used(version)?;
+        let mut command = Command::new(engine.path);
+        command.args(args);
+
+        exec_command(command).map_err(Into::into)
+    }
+
+    /// Mark engine version as used now, and return its info.
+    pub fn mark_as_used(&self, version: &GodotVersion) -> Result<EngineInfo, EngineError> {
+        let mut engine = self.get_version(version)?;
+        engine.last_used = Some(unix_timestamp());
+        self.update_all(vec![engine.clone()])?;
+
+        Ok(engine)
+    }
+
+    /// Get engine installation folder, if installed by gdpm.
+    pub fn get_install_path(&self, version: &GodotVersion) -> Result<Option<PathBuf>, EngineError> {
+        let udir = UserDir::new(self.io_adapter);
+        let version_path = udir
+            .get_or_create_directory(Path::new(ENGINE_DIR))?
+            .join(version.to_string());
+
+        Ok(self
+            .io_adapter
+            .path_exists(&version_path)
+            .then_some(version_path))
+    }
+
+    /// Get disk usage of registered engines and their export templates.
+    pub fn disk_usage(&self) -> Result<Vec<EngineDiskUsage>, EngineError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let mut usage = vec![];
+
+        for engine in self.list()? {
+            let engine_size = match self.get_install_path(&engine.version)? {
+                Some(p) => Some(self.io_adapter.get_size(&p)?),
+                None => None,
+            };
+            let templates_path = gdir.get_specific_export_templates_directory(&engine.version)?;
+            let templates_size = if self.io_adapter.path_exists(&templates_path) {
+                Some(self.io_adapter.get_size(&templates_path)?)
+            } else {
+                None
+            };
+
+            usage.push(EngineDiskUsage {
+                engine,
+                engine_size,
+                templates_size,
+            });
+        }
+
+        Ok(usage)
+    }
+
+    /// Get engines which can be pruned.
+    ///
+    /// Only engines installed by gdpm are considered, and the default engine is kept.
+    /// An engine is kept if it is part of `referenced` (when given), or if it has been
+    /// used during the last `unused_for` duration (when given).
+    pub fn get_prune_candidates(
+        &self,
+        referenced: Option<&[GodotVersion]>,
+        unused_for: Option<Duration>,
+    ) -> Result<Vec<EngineInfo>, EngineError> {
+        let default = self.get_default()?;
+        let now = unix_timestamp();
+        let mut candidates = vec![];
+
+        for engine in self.list()? {
+            let is_default = default.as_ref() == Some(&engine.version);
+            let is_referenced = referenced
+                .map(|r| r.contains(&engine.version))
+                .unwrap_or(false);
+            let is_recent = match (unused_for, engine.last_used) {
+                (Some(d), Some(t)) => now.saturating_sub(t) < d.as_secs(),
+                // Usage is unknown for engines registered before it was tracked
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+
+            if is_default
+                || is_referenced
+                || is_recent
+                || self.get_install_path(&engine.version)?.is_none()
+            {
+                continue;
+            }
+
+            candidates.push(engine);
+        }
+
+        Ok(candidates)
+    }
+
+    /// Expand an alias requirement using the `[aliases]` configuration section.
+    ///
+    /// Other requirements are returned as-is.
+    pub fn expand_alias(&self, req: &GodotVersionReq) -> Result<GodotVersionReq, EngineError> {
+        let Some(name) = req.alias() else {
+            return Ok(req.clone());
+        };
+
+        let gconf = GlobalConfig::new(self.io_adapter);
+        let target = gconf
+            .get_alias(name)?
+            .ok_or_else(|| EngineError::UnknownAlias(name.into()))?;
+        if target.alias().is_some() {
+            return Err(EngineError::InvalidAlias(name.into(), target.to_string()));
+        }
+
+        let mono = target.mono() || req.mono();
+        Ok(target.with_mono(mono).with_pre_release(req.pre_release()))
+    }
+
     /// Set engine as default.
     pub fn set_as_default(&self, version: &GodotVersion) -> Result<(), EngineError> {
         // Assert the engine exists
@@ -285,210 +716,670 @@ impl<'a, I: IoAdapter> EngineHandler<'a, I> {
         );
 
         let gconf = GlobalConfig::new(self.io_adapter);
-        let mut configuration = gconf.load()?;
-        configuration.set_property("", "default_engine", GdValue::String(version.to_string()));
-        gconf.save(configuration).map_err(Into::into)
+        gconf.update(|configuration| {
+            configuration.set_property("", "default_engine", GdValue::String(version.to_string()));
+            Ok(())
+        })
+    }
+
+    /// Set an engine alias as default (e.g. `lts` or `lts.mono`), so that updating the alias
+    /// changes the default engine.
+    pub fn set_default_alias(&self, req: &GodotVersionReq) -> Result<(), EngineError> {
+        // Assert the alias resolves to an engine
+        self.resolve(req)?;
+
+        let name = req.to_string();
+        debug!("Setting alias '{}' as default ...", name.color("green"));
+
+        let gconf = GlobalConfig::new(self.io_adapter);
+        gconf.update(|configuration| {
+            configuration.set_property("", "default_engine", GdValue::String(name));
+            Ok(())
+        })
+    }
+
+    /// Get default engine alias, if the default engine is set using an alias.
+    pub fn get_default_alias(&self) -> Result<Option<String>, EngineError> {
+        Ok(self
+            .read_default_engine()?
+            .filter(|e| parse_alias_req(e).is_some()))
+    }
+
+    fn read_default_engine(&self) -> Result<Option<String>, EngineError> {
+        let gconf = GlobalConfig::new(self.io_adapter);
+        Ok(gconf.load().map(|x| {
+            x.get_property("", "default_engine")
+                .and_then(|x| x.to_str())
+        })?)
     }
 
     /// Unset default engine.
     pub fn unset_default(&self) -> Result<(), EngineError> {
         let gconf = GlobalConfig::new(self.io_adapter);
-        let mut configuration = gconf.load()?;
-        configuration.remove_property("", "default_engine")?;
-        gconf.save(configuration).map_err(Into::into)
+        gconf.update(|configuration| {
+            configuration.remove_property("", "default_engine")?;
+            Ok(())
+        })
     }
 
     /// Get default engine.
     pub fn get_default(&self) -> Result<Option<GodotVersion>, EngineError> {
-        let gconf = GlobalConfig::new(self.io_adapter);
-        let default_engine = gconf.load().map(|x| {
-            x.get_property("", "default_engine")
-                .and_then(|x| x.to_str())
-        })?;
+        let default_engine = self.read_default_engine()?;
 
         if let Some(e) = &default_engine {
-            // Assert the version exist
-            let version = GodotVersion::from_str(e)?;
-            self.get_version(&version)?;
+            if let Some(req) = parse_alias_req(e) {
+                return match self.resolve(&req) {
+                    Ok(engine) => Ok(Some(engine.version)),
+                    Err(err) => {
+                        debug!("Ignoring default engine alias '{}': {}", e, err);
+                        Ok(None)
+                    }
+                };
+            }
+
+            // Ignore stale default engines, see `check`
+            let version = match GodotVersion::from_str(e) {
+                Ok(v) => v,
+                Err(err) => {
+                    debug!("Ignoring invalid default engine '{}': {}", e, err);
+                    return Ok(None);
+                }
+            };
+
+            if self.has_version(&version)?.is_none() {
+                debug!("Ignoring default engine '{}', which is not registered", e);
+                return Ok(None);
+            }
+
             return Ok(Some(version));
         }
 
         Ok(None)
     }
 
+    /// Check engines configuration for invalid entries.
+    pub fn check(&self) -> Result<Vec<EngineConfigIssue>, EngineError> {
+        let gconf = GlobalConfig::new(self.io_adapter);
+        let config = gconf.load()?;
+        let mut issues = vec![];
+        let mut engines = vec![];
+
+        for (key, value) in config.get_section(ENGINES_SECTION).unwrap_or_default() {
+            match EngineInfo::from_gdvalue(value) {
+                Ok(e) if !self.io_adapter.path_is_file(&e.path) => {
+                    issues.push(EngineConfigIssue::MissingExecutable(key, e.path))
+                }
+                Ok(e) => engines.push(e),
+                Err(EngineError::InvalidEngineEntry(reason)) => {
+                    issues.push(EngineConfigIssue::InvalidEntry(key, reason))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if let Some(default) = config
+            .get_property("", "default_engine")
+            .and_then(|x| x.to_str())
+        {
+            let registered = if parse_alias_req(&default).is_some() {
+                self.get_default()?.is_some()
+            } else {
+                GodotVersion::from_str(&default)
+                    .map(|v| engines.iter().any(|e| e.version == v))
+                    .unwrap_or(false)
+            };
+            if !registered {
+                issues.push(EngineConfigIssue::InvalidDefault(default));
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Repair engines configuration issues, by removing invalid entries.
+    ///
+    /// If the default engine is removed, the first valid engine becomes the default one.
+    pub fn repair(&self, issues: &[EngineConfigIssue]) -> Result<(), EngineError> {
+        let gconf = GlobalConfig::new(self.io_adapter);
+        gconf.update(|config| {
+            for issue in issues {
+                match issue {
+                    EngineConfigIssue::InvalidEntry(key, _)
+                    | EngineConfigIssue::MissingExecutable(key, _) => {
+                        config.remove_property(ENGINES_SECTION, key)?;
+                    }
+                    EngineConfigIssue::InvalidDefault(_) => {
+                        config.remove_property("", "default_engine")?;
+                    }
+                }
+            }
+
+            Ok::<_, EngineError>(())
+        })?;
+
+        if self.get_default()?.is_none() {
+            if let Some(engine) = self.get_latest()? {
+                self.set_as_default(&engine.version)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Install engine version from official zip.
+    ///
+    /// The engine is extracted in a staging folder, then moved to its final folder and
+    /// registered. If any step fails, the staging folder is removed and a previous
+    /// installation of the same version is restored.
+    ///
+    /// The zip file is removed once installed.
     pub fn install_from_official_zip(
         &self,
-        zip_data: Vec<u8>,
+        zip_path: &Path,
         version: GodotVersion,
         system: SystemVersion,
     ) -> Result<PathBuf, EngineError> {
         let udir = UserDir::new(self.io_adapter);
         let engine_path = udir.get_or_create_directory(Path::new(ENGINE_DIR))?;
         let version_name = format!("{}", version);
-        let temp_name = "temp";
-        let version_path = udir.get_or_create_directory(&engine_path.join(&version_name))?;
-        let extraction_path =
-            udir.get_or_create_directory(&engine_path.join(&version_name).join(temp_name))?;
-        let zip_path = version_path.join("download").with_extension("zip");
-        self.io_adapter.write_bytes_to_file(&zip_path, &zip_data)?;
-
-        // Unzip
-        self.io_adapter
-            .open_and_extract_zip(&zip_path, &extraction_path)?;
-
-        // Folder name
-        let zip_folder_name = format!(
-            "Godot_v{}-{}_{}",
-            version.version(),
-            version.kind(),
-            system.get_archive_basename(version.mono())
-        );
-        let zip_folder_path = extraction_path.join(&zip_folder_name);
-
-        // Mono versions have an additional folder
-        let zip_exec_path = if version.mono() {
-            let zip_exec_name = format!("{}.{}", &zip_folder_name, system.get_extension());
-            extraction_path.join(&zip_folder_name).join(zip_exec_name)
-        } else {
-            zip_folder_path.with_extension(system.get_extension())
+        let version_path = engine_path.join(&version_name);
+        let staging_path = engine_path.join(format!(".{}.staging", version_name));
+        let backup_path = engine_path.join(format!(".{}.backup", version_name));
+
+        // Staging and backup folders are shared by concurrent installations of this version
+        let _lock = udir.lock_file(&version_path)?;
+        self.clean_interrupted_install(&version_path, &staging_path, &backup_path)?;
+
+        // Extract in staging folder
+        self.io_adapter.create_dir(&staging_path)?;
+        let exec_path = match self.stage_official_zip(zip_path, &version, &system, &staging_path) {
+            Ok(p) => p,
+            Err(e) => {
+                self.remove_dir_quietly(&staging_path);
+                return Err(e);
+            }
         };
 
-        let zip_exec_target = Path::new(&version_path)
-            .join(GODOT_EXECUTABLE_NAME)
-            .with_extension(system.get_extension());
+        let has_previous = self.move_staged_dir(&staging_path, &version_path, &backup_path)?;
 
-        // Copy to current dir
-        self.io_adapter
-            .copy_file(&zip_exec_path, &zip_exec_target)?;
-        if version.mono() {
-            let mono_folder_src = zip_folder_path.join("GodotSharp");
-            let mono_folder_dst = &version_path;
-            self.io_adapter
-                .copy_dir(&mono_folder_src, mono_folder_dst)?;
+        // Register
+        let exec_path = version_path.join(exec_path);
+        if let Err(e) = self.register_installed_engine(&version_name, &exec_path) {
+            self.remove_dir_quietly(&version_path);
+            self.restore_backup(&version_path, &backup_path, has_previous);
+            if !has_previous {
+                // Do not keep a registration for the removed executable
+                if let Ok(Some(_)) = self.has_version(&version) {
+                    let _ = self.unregister(&version);
+                }
+            }
+
+            return Err(e);
         }
 
         // Cleaning
-        self.io_adapter.remove_dir_all(&extraction_path)?;
-        self.io_adapter.remove_file(&zip_path)?;
-
-        // Register
-        self.register(EngineInfo::new(
-            self.io_adapter,
-            GodotVersion::from_str(&version_name)?,
-            zip_exec_target.clone(),
-        )?)?;
+        if has_previous {
+            self.remove_dir_quietly(&backup_path);
+        }
+        if let Err(e) = self.io_adapter.remove_file(zip_path) {
+            warn!("Could not remove archive '{}': {}", zip_path.display(), e);
+        }
 
-        Ok(zip_exec_target)
+        Ok(exec_path)
     }
 
-    /// Install export templates.
-    pub fn install_export_templates(
+    /// Extract an official zip in a staging folder, with the same layout as an installed engine.
+    ///
+    /// Returns the executable path, relative to the staging folder.
+    fn stage_official_zip(
         &self,
-        templates_data: Vec<u8>,
-        version: GodotVersion,
+        zip_path: &Path,
+        version: &GodotVersion,
+        system: &SystemVersion,
+        staging_path: &Path,
     ) -> Result<PathBuf, EngineError> {
-        let gdir = GodotDir::new(self.io_adapter);
-        let templates_directory = gdir.get_or_create_export_templates_directory()?;
-        let templates_path_for_version =
-            templates_directory.join(version.get_export_template_name());
-
-        if !self.io_adapter.path_exists(&templates_path_for_version) {
-            self.io_adapter.create_dir(&templates_path_for_version)?;
-        }
+        let extraction_path = staging_path.join("temp");
+        self.io_adapter.create_dir(&extraction_path)?;
 
         // Unzip
-        let zip_path = templates_path_for_version.join("templates.tpz");
-        self.io_adapter
-            .write_bytes_to_file(&zip_path, &templates_data)?;
         self.io_adapter
-            .open_and_extract_zip(&zip_path, &templates_path_for_version)?;
+            .open_and_extract_zip(zip_path, &extraction_path)?;
+
+        let prefix = format!("Godot_v{}-{}_", version.version(), version.kind());
+        let exec_path = if system.is_macos() {
+            // MacOS archives contain an app bundle
+            let app_name = if version.mono() {
+                "Godot_mono.app"
+            } else {
+                "Godot.app"
+            };
+            self.io_adapter
+                .copy_dir(&extraction_path.join(app_name), staging_path)?;
+            Path::new(app_name)
+                .join("Contents")
+                .join("MacOS")
+                .join("Godot")
+        } else {
+            let zip_exec_name = format!("{}{}", prefix, system.get_executable_basename(version)?);
+            let exec_path = Path::new(GODOT_EXECUTABLE_NAME).with_extension(system.get_extension());
+            let zip_exec_target = staging_path.join(&exec_path);
+
+            // Mono versions have an additional folder
+            if version.mono() {
+                let zip_folder_path = extraction_path.join(format!(
+                    "{}{}",
+                    prefix,
+                    system.get_archive_basename(version)?
+                ));
+                self.io_adapter
+                    .copy_file(&zip_folder_path.join(zip_exec_name), &zip_exec_target)?;
+                self.io_adapter
+                    .copy_dir(&zip_folder_path.join("GodotSharp"), staging_path)?;
+            } else {
+                self.io_adapter
+                    .copy_file(&extraction_path.join(zip_exec_name), &zip_exec_target)?;
+            }
 
-        // Remove templates.tpz
-        self.io_adapter.remove_file(&zip_path)?;
+            exec_path
+        };
 
-        // Move files in top-level folder
-        self.io_adapter
-            .move_files_in_parent_folder(&templates_path_for_version.join("templates"))?;
+        self.io_adapter.remove_dir_all(&extraction_path)?;
 
-        Ok(templates_path_for_version)
+        Ok(exec_path)
     }
 
-    /// Uninstall version.
-    pub fn uninstall(&self, version: &GodotVersion) -> Result<(), EngineError> {
-        let udir = UserDir::new(self.io_adapter);
-        let gdir = GodotDir::new(self.io_adapter);
-        let engine_path = udir.get_or_create_directory(Path::new(ENGINE_DIR))?;
-        let version_path = engine_path.join(version.to_string());
-        self.get_version(version)?;
-
-        // Remove export templates
-        let export_templates_path = gdir.get_specific_export_templates_directory(version)?;
-        if self.io_adapter.path_exists(&export_templates_path) {
-            info!(
-                "Removing export templates {} ...",
-                export_templates_path.display().to_string().color("green")
-            );
-            self.io_adapter.remove_dir_all(&export_templates_path)?;
+    fn register_installed_engine(
+        &self,
+        version_name: &str,
+        exec_path: &Path,
+    ) -> Result<(), EngineError> {
+        let mut entry = EngineInfo::new(
+            self.io_adapter,
+            GodotVersion::from_str(version_name)?,
+            exec_path.to_owned(),
+        )?;
+        match self.update_build_info(&mut entry) {
+            Ok(Some(build)) => warn!(
+                "Engine at path '{}' reports version '{}' instead of '{}'.",
+                entry.path.display(),
+                build.version,
+                entry.version
+            ),
+            Ok(None) => (),
+            Err(e) => debug!("Could not detect engine build: {}", e),
         }
 
-        if self.io_adapter.path_exists(&version_path) {
-            self.unregister(version)?;
+        self.register(entry)
+    }
 
-            info!(
-                "Removing {} ...",
-                version_path.display().to_string().color("green")
+    /// Clean folders left by an interrupted installation.
+    ///
+    /// A previous installation which was moved aside is restored.
+    fn clean_interrupted_install(
+        &self,
+        version_path: &Path,
+        staging_path: &Path,
+        backup_path: &Path,
+    ) -> Result<(), EngineError> {
+        if self.io_adapter.path_exists(staging_path) {
+            debug!(
+                "Removing staging folder '{}' from an interrupted installation ...",
+                staging_path.display()
             );
-            self.io_adapter.remove_dir_all(&version_path)?;
-        } else {
-            return Err(EngineError::EngineNotInstalled(version.to_owned()));
+            self.io_adapter.remove_dir_all(staging_path)?;
+        }
+
+        if self.io_adapter.path_exists(backup_path) {
+            if self.io_adapter.path_exists(version_path) {
+                self.io_adapter.remove_dir_all(backup_path)?;
+            } else {
+                self.io_adapter.rename(backup_path, version_path)?;
+            }
         }
 
         Ok(())
     }
 
-    /// Cache versions.
-    pub fn write_versions_in_cache(&self, versions: Vec<GodotVersion>) -> Result<(), EngineError> {
-        let udir = UserDir::new(self.io_adapter);
-        let path = udir.get_or_create_file(Path::new("remote-cache.cfg"))?;
+    /// Move a staging folder to its final path, moving the previous folder aside to a backup path.
+    ///
+    /// Returns whether a previous folder was moved aside. The staging folder is removed on failure.
+    fn move_staged_dir(
+        &self,
+        staging_path: &Path,
+        final_path: &Path,
+        backup_path: &Path,
+    ) -> Result<bool, EngineError> {
+        let has_previous = self.io_adapter.path_exists(final_path);
+        if has_previous {
+            if let Err(e) = self.io_adapter.rename(final_path, backup_path) {
+                self.remove_dir_quietly(staging_path);
+                return Err(e.into());
+            }
+        }
 
-        let mut data = GdSettingsMap::new();
-        for version in versions {
+        if let Err(e) = self.io_adapter.rename(staging_path, final_path) {
+            self.remove_dir_quietly(staging_path);
+            self.restore_backup(final_path, backup_path, has_previous);
+            return Err(e.into());
+        }
+
+        Ok(has_previous)
+    }
+
+    fn restore_backup(&self, version_path: &Path, backup_path: &Path, has_backup: bool) {
+        if has_backup {
+            if let Err(e) = self.io_adapter.rename(backup_path, version_path) {
+                warn!(
+                    "Could not restore previous installation from '{}': {}",
+                    backup_path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn remove_dir_quietly(&self, path: &Path) {
+        if self.io_adapter.path_exists(path) {
+            if let Err(e) = self.io_adapter.remove_dir_all(path) {
+                warn!("Could not remove folder '{}': {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Install export templates from a templates archive.
+    ///
+    /// The archive is removed once installed.
+    pub fn install_export_templates(
+        &self,
+        templates_path: &Path,
+        version: GodotVersion,
+    ) -> Result<PathBuf, EngineError> {
+        let templates_path_for_version = self.extract_export_templates(templates_path, version)?;
+
+        // Remove templates archive
+        self.io_adapter.remove_file(templates_path)?;
+
+        Ok(templates_path_for_version)
+    }
+
+    /// Extract export templates from a templates archive (`.tpz`).
+    ///
+    /// Templates are extracted in a staging folder, then replace existing export templates
+    /// for this version, which are kept if anything fails.
+    pub fn extract_export_templates(
+        &self,
+        templates_path: &Path,
+        version: GodotVersion,
+    ) -> Result<PathBuf, EngineError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_directory = gdir.get_or_create_export_templates_directory()?;
+        let templates_name = version.get_export_template_name();
+        let templates_path_for_version = templates_directory.join(&templates_name);
+        let staging_path = templates_directory.join(format!(".{}.staging", templates_name));
+        let backup_path = templates_directory.join(format!(".{}.backup", templates_name));
+
+        let _lock = UserDir::new(self.io_adapter).lock_file(&templates_path_for_version)?;
+        self.clean_interrupted_install(&templates_path_for_version, &staging_path, &backup_path)?;
+
+        // Extract in staging folder, then move files in top-level folder
+        self.io_adapter.create_dir(&staging_path)?;
+        let staged = self
+            .io_adapter
+            .open_and_extract_zip(templates_path, &staging_path)
+            .and_then(|_| {
+                self.io_adapter
+                    .move_files_in_parent_folder(&staging_path.join("templates"))
+            });
+        if let Err(e) = staged {
+            self.remove_dir_quietly(&staging_path);
+            return Err(e.into());
+        }
+
+        let has_previous =
+            self.move_staged_dir(&staging_path, &templates_path_for_version, &backup_path)?;
+        if has_previous {
+            self.remove_dir_quietly(&backup_path);
+        }
+
+        Ok(templates_path_for_version)
+    }
+
+    /// List installed export templates, sorted by version.
+    pub fn list_export_templates(&self) -> Result<Vec<(GodotVersion, PathBuf)>, EngineError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_directory = gdir.get_or_create_export_templates_directory()?;
+        let mut templates = vec![];
+
+        for entry in self.io_adapter.read_dir(&templates_directory)? {
+            let path = entry
+                .map_err(|e| Error::ReadDirEntryError(templates_directory.clone(), e.to_string()))?
+                .path();
+            if self.io_adapter.path_is_file(&path) {
+                continue;
+            }
+
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match GodotVersion::from_str(&name) {
+                Ok(v) => templates.push((v, path)),
+                Err(e) => debug!("Ignoring export templates folder '{}': {}", name, e),
+            }
+        }
+
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(templates)
+    }
+
+    /// Uninstall export templates for a version.
+    pub fn uninstall_export_templates(
+        &self,
+        version: &GodotVersion,
+    ) -> Result<PathBuf, EngineError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_path = gdir.get_specific_export_templates_directory(version)?;
+        if !self.io_adapter.path_exists(&templates_path) {
+            return Err(EngineError::ExportTemplatesNotInstalled(version.clone()));
+        }
+
+        self.io_adapter.remove_dir_all(&templates_path)?;
+        Ok(templates_path)
+    }
+
+    /// Verify installed export templates for a version.
+    pub fn verify_export_templates(
+        &self,
+        version: &GodotVersion,
+    ) -> Result<Vec<ExportTemplatesIssue>, EngineError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_path = gdir.get_specific_export_templates_directory(version)?;
+        if !self.io_adapter.path_exists(&templates_path) {
+            return Err(EngineError::ExportTemplatesNotInstalled(version.clone()));
+        }
+
+        let mut issues = vec![];
+        let version_file = templates_path.join(EXPORT_TEMPLATES_VERSION_FILE);
+        if self.io_adapter.path_is_file(&version_file) {
+            let expected = version.get_export_template_name();
+            let found = self
+                .io_adapter
+                .read_file_to_string(&version_file)?
+                .trim()
+                .to_string();
+            if found != expected {
+                issues.push(ExportTemplatesIssue::VersionMismatch(expected, found));
+            }
+        } else {
+            issues.push(ExportTemplatesIssue::MissingVersionFile(
+                version_file.clone(),
+            ));
+        }
+
+        let has_templates = self
+            .io_adapter
+            .read_dir(&templates_path)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .any(|p| p != version_file && self.io_adapter.path_is_file(&p));
+        if !has_templates {
+            issues.push(ExportTemplatesIssue::NoTemplates);
+        }
+
+        Ok(issues)
+    }
+
+    /// Uninstall version.
+    pub fn uninstall(&self, version: &GodotVersion) -> Result<(), EngineError> {
+        let udir = UserDir::new(self.io_adapter);
+        let gdir = GodotDir::new(self.io_adapter);
+        let engine_path = udir.get_or_create_directory(Path::new(ENGINE_DIR))?;
+        let version_path = engine_path.join(version.to_string());
+        self.get_version(version)?;
+
+        // Remove export templates
+        let export_templates_path = gdir.get_specific_export_templates_directory(version)?;
+        if self.io_adapter.path_exists(&export_templates_path) {
+            info!(
+                "Removing export templates {} ...",
+                export_templates_path.display().to_string().color("green")
+            );
+            self.io_adapter.remove_dir_all(&export_templates_path)?;
+        }
+
+        if self.io_adapter.path_exists(&version_path) {
+            self.unregister(version)?;
+
+            info!(
+                "Removing {} ...",
+                version_path.display().to_string().color("green")
+            );
+            self.io_adapter.remove_dir_all(&version_path)?;
+        } else {
+            return Err(EngineError::EngineNotInstalled(version.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    /// Cache remote versions, with the current time.
+    pub fn write_versions_in_cache(&self, versions: &[GodotVersion]) -> Result<(), EngineError> {
+        let udir = UserDir::new(self.io_adapter);
+        let path = udir.get_or_create_file(Path::new(REMOTE_CACHE_FILE))?;
+
+        let mut data = GdSettingsMap::new();
+        for version in versions {
             data.insert(version.to_string(), version.to_gdvalue());
         }
         let mut sections = GdSettingsType::new();
         sections.insert("remote".into(), data);
-        let settings = GdSettings::new(sections);
+        let mut settings = GdSettings::new(sections);
+        settings.set_property(
+            "",
+            "updated_at",
+            GdValue::String(unix_timestamp().to_string()),
+        );
 
         udir.write_string_to_file(&path, &settings.to_string())?;
         Ok(())
     }
 
-    /// Read versions from cache.
-    pub fn read_versions_from_cache(&self) -> Result<Vec<GodotVersion>, EngineError> {
+    /// Read remote versions from cache, sorted from oldest to newest.
+    ///
+    /// Returns `None` if the cache is empty, malformed or older than `ttl`.
+    pub fn read_versions_from_cache(
+        &self,
+        ttl: Duration,
+    ) -> Result<Option<Vec<GodotVersion>>, EngineError> {
         let udir = UserDir::new(self.io_adapter);
-        let path = udir.get_or_create_file(Path::new("remote-cache.cfg"))?;
+        let path = udir.get_or_create_file(Path::new(REMOTE_CACHE_FILE))?;
         let contents = udir.read_file_to_string(&path)?;
-        let settings = parse_gdsettings_file(&contents)
-            .map_err(|e| EngineError::ConfigError(ConfigError::MalformedSettings(e)))?;
-        let mut versions = vec![];
-
-        if let Some(section) = settings.get_section("remote") {
-            let values = section
-                .into_values()
-                .filter_map(GodotVersion::from_gdvalue)
-                .collect();
-            versions = values;
+        let settings = match parse_gdsettings_file(&contents) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(error = %e.with_path(&path), "Remote versions cache is malformed, ignoring it.");
+                return Ok(None);
+            }
+        };
+
+        let updated_at = settings
+            .get_property("", "updated_at")
+            .and_then(|x| x.to_str())
+            .and_then(|x| x.parse::<u64>().ok());
+        match updated_at {
+            Some(t) if unix_timestamp().saturating_sub(t) < ttl.as_secs() => (),
+            _ => {
+                debug!("Remote versions cache is missing or expired.");
+                return Ok(None);
+            }
         }
 
-        Ok(versions)
+        let mut versions: Vec<_> = settings
+            .get_section("remote")
+            .map(|section| {
+                section
+                    .into_values()
+                    .filter_map(GodotVersion::from_gdvalue)
+                    .collect()
+            })
+            .unwrap_or_default();
+        versions.sort();
+
+        Ok(Some(versions))
     }
 }
 
+/// Parse a default engine value as an alias requirement (e.g. `lts` or `lts.mono`).
+fn parse_alias_req(value: &str) -> Option<GodotVersionReq> {
+    GodotVersionReq::from_str(value)
+        .ok()
+        .filter(|r| r.alias().is_some())
+}
+
+/// Check if a file name looks like a Godot engine executable.
+fn is_engine_candidate(name: &str) -> bool {
+    const IGNORED_EXTENSIONS: &[&str] = &[
+        "bat", "cfg", "cmd", "dll", "dylib", "icns", "json", "md", "pck", "pdb", "png", "sh", "so",
+        "svg", "tpz", "txt", "xml", "zip",
+    ];
+
+    let name = name.to_lowercase();
+    let extension = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+
+    name.starts_with("godot")
+        && !name.starts_with("godotsharp")
+        && !name.contains("console")
+        && !IGNORED_EXTENSIONS.contains(&extension)
+        && (cfg!(not(windows)) || extension == "exe")
+}
+
+#[cfg(unix)]
+fn exec_command(mut command: Command) -> Result<i32, Error> {
+    use std::os::unix::process::CommandExt;
+
+    // Only returns on error
+    Err(Error::CommandExecutionError(command.exec().to_string()))
+}
+
+#[cfg(not(unix))]
+fn exec_command(mut command: Command) -> Result<i32, Error> {
+    command
+        .status()
+        .map(|s| s.code().unwrap_or(1))
+        .map_err(|e| Error::CommandExecutionError(e.to_string()))
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     mod engineinfo {
-        use gdsettings_parser::GdSettings;
+        use gdsettings_parser::{GdSettings, GdValue};
         use mockall::predicate;
         use std::{
             path::{Path, PathBuf},
@@ -497,6 +1388,7 @@ mod tests {
 
         use crate::engine::EngineInfo;
         use gdpm_io::MockIoAdapter;
+        use gdpm_types::version::GodotBuildInfo;
 
         macro_rules! gdv {
             ($s:expr) => {
@@ -530,12 +1422,38 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_matches_build() {
+            let build = |s: &str| s.parse::<GodotBuildInfo>().unwrap();
+            let mut entry = EngineInfo {
+                path: PathBuf::from("/godot"),
+                version: gdv!("4.3"),
+                hash: None,
+                last_used: None,
+            };
+
+            assert!(entry.matches_build(&build("4.3.stable.official.77dcf97d8")));
+            assert!(!entry.matches_build(&build("4.3.stable.mono.official.77dcf97d8")));
+            assert!(!entry.matches_build(&build("4.2.stable.official.46dc27791")));
+
+            entry.hash = Some("77dcf97d8".into());
+            assert!(entry.matches_build(&build("4.3.stable.official.77dcf97d8")));
+            assert!(!entry.matches_build(&build("4.3.stable.official.a1b2c3d4e")));
+
+            entry.version = gdv!("4.3.custom.mybranch");
+            entry.hash = None;
+            assert!(entry.matches_build(&build("4.3.stable.custom_build.77dcf97d8")));
+            assert!(!entry.matches_build(&build("4.3.stable.official.77dcf97d8")));
+            assert!(!entry.matches_build(&build("4.2.stable.custom_build.77dcf97d8")));
+        }
+
         #[test]
         fn test_from_settings() {
             let settings = indoc::indoc! {r#"
                 [engines]
-                1-0-0 = { "path": "/hello", "version": "1.0.0" }
+                1-0-0 = { "path": "/hello", "version": "1.0.0", "hash": "77dcf97d8", "last_used": 1700000000 }
                 2-0-0 = { "path": "/hi", "version": "2.0.0" }
+                3-0-0 = { "path": "/legacy", "version": "3.0.0", "last_used": "1600000000" }
             "#};
 
             let settings = GdSettings::from_str(settings).unwrap();
@@ -546,17 +1464,1080 @@ mod tests {
                 vec![
                     EngineInfo {
                         path: PathBuf::from("/hello"),
-                        version: gdv!("1.0.0")
+                        version: gdv!("1.0.0"),
+                        hash: Some("77dcf97d8".into()),
+                        last_used: Some(1700000000)
                     },
                     EngineInfo {
                         path: PathBuf::from("/hi"),
-                        version: gdv!("2.0.0")
+                        version: gdv!("2.0.0"),
+                        hash: None,
+                        last_used: None
+                    },
+                    EngineInfo {
+                        path: PathBuf::from("/legacy"),
+                        version: gdv!("3.0.0"),
+                        hash: None,
+                        last_used: Some(1600000000)
                     }
                 ]
-            )
+            );
+
+            // Timestamps are written as integers
+            assert_eq!(
+                engine_list[2].to_gdvalue().to_object().unwrap()["last_used"],
+                GdValue::Int(1600000000)
+            );
+            assert!(!engine_list[0].is_version_4());
+            assert!(EngineInfo::from_gdvalue(GdValue::Object(vec![
+                ("path".into(), GdValue::String("/godot".into())),
+                ("version".into(), GdValue::String("4.10".into()))
+            ]))
+            .unwrap()
+            .is_version_4());
         }
     }
 
-    #[test]
-    fn test_from_settings() {}
+    mod enginehandler {
+        use std::{
+            path::{Path, PathBuf},
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+            time::Duration,
+        };
+
+        use gdpm_downloader::config::DownloadConfig;
+        use gdpm_io::{DefaultIoAdapter, Error, IoAdapter, MockIoAdapter};
+        use mockall::predicate;
+
+        use gdpm_types::version::{GodotVersion, GodotVersionReq, SystemVersion};
+
+        use crate::{
+            engine::{
+                is_engine_candidate, EngineConfigIssue, EngineHandler, EngineInfo,
+                ExportTemplatesIssue, ScannedEngine,
+            },
+            error::EngineError,
+        };
+
+        fn config_adapter(contents: &'static str) -> MockIoAdapter {
+            file_adapter("gdpm.cfg", contents)
+        }
+
+        fn file_adapter(name: &'static str, contents: &'static str) -> MockIoAdapter {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter
+                .expect_path_exists()
+                .with(predicate::function(|p: &Path| p.starts_with("/home/user")))
+                .returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(
+                    Path::new("/home/user/.config/gdpm").join(name),
+                ))
+                .returning(move |_| Ok(contents.into()));
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+            adapter
+        }
+
+        const ENGINES_DIR: &str = "/home/user/.config/gdpm/engines";
+        const DOWNLOAD_ZIP: &str = "/home/user/.config/gdpm/downloads/download.zip";
+
+        fn engines_path(name: &str) -> PathBuf {
+            Path::new(ENGINES_DIR).join(name)
+        }
+
+        fn lock_name(prefix: &'static str) -> impl mockall::Predicate<Path> {
+            predicate::function(move |p: &Path| {
+                p.file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with(prefix))
+            })
+        }
+
+        /// Adapter extracting an official 4.3 zip in the staging folder.
+        ///
+        /// Expectations from the swap step onwards are left to each test.
+        fn install_adapter(has_previous: bool) -> MockIoAdapter {
+            let mut adapter = MockIoAdapter::new();
+            let staging = engines_path(".4.3.staging");
+            let extraction = staging.join("temp");
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter
+                .expect_path_exists()
+                .with(predicate::in_iter([
+                    PathBuf::from("/home/user/.config/gdpm"),
+                    PathBuf::from(ENGINES_DIR),
+                ]))
+                .returning(|_| true);
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+
+            // The version folder stays locked during the whole installation
+            adapter
+                .expect_lock_file()
+                .with(lock_name("4.3-"))
+                .times(1)
+                .returning(|_| Ok(Default::default()));
+
+            // No interrupted installation
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(staging.clone()))
+                .times(1)
+                .returning(|_| false);
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(engines_path(".4.3.backup")))
+                .times(1)
+                .returning(|_| false);
+
+            // Extraction
+            adapter
+                .expect_create_dir()
+                .with(predicate::eq(staging.clone()))
+                .times(1)
+                .returning(|_| Ok(()));
+            adapter
+                .expect_create_dir()
+                .with(predicate::eq(extraction.clone()))
+                .times(1)
+                .returning(|_| Ok(()));
+            adapter
+                .expect_open_and_extract_zip()
+                .with(
+                    predicate::eq(Path::new(DOWNLOAD_ZIP)),
+                    predicate::eq(extraction.clone()),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+            adapter
+                .expect_copy_file()
+                .with(
+                    predicate::eq(extraction.join("Godot_v4.3-stable_linux.x86_64")),
+                    predicate::eq(staging.join("godot.x11")),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(extraction))
+                .times(1)
+                .returning(|_| Ok(()));
+
+            // Previous installation
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(engines_path("4.3")))
+                .times(1)
+                .returning(move |_| has_previous);
+
+            adapter
+        }
+
+        fn expect_rename(
+            adapter: &mut MockIoAdapter,
+            source: &str,
+            destination: &str,
+            fails: bool,
+        ) {
+            adapter
+                .expect_rename()
+                .with(
+                    predicate::eq(engines_path(source)),
+                    predicate::eq(engines_path(destination)),
+                )
+                .times(1)
+                .returning(move |s, d| {
+                    if fails {
+                        return Err(Error::RenameError(s.into(), d.into(), "denied".into()));
+                    }
+                    Ok(())
+                });
+        }
+
+        fn expect_remove_dir(adapter: &mut MockIoAdapter, name: &str) {
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(engines_path(name)))
+                .times(1)
+                .returning(|_| true);
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(engines_path(name)))
+                .times(1)
+                .returning(|_| Ok(()));
+        }
+
+        fn install(adapter: &MockIoAdapter) -> Result<PathBuf, EngineError> {
+            EngineHandler::new(adapter).install_from_official_zip(
+                Path::new(DOWNLOAD_ZIP),
+                GodotVersion::try_from("4.3").unwrap(),
+                SystemVersion::X1164,
+            )
+        }
+
+        #[test]
+        fn test_install_from_official_zip() {
+            let mut adapter = install_adapter(true);
+            let exec_path = engines_path("4.3").join("godot.x11");
+            let config_path = Path::new("/home/user/.config/gdpm/gdpm.cfg");
+            expect_rename(&mut adapter, "4.3", ".4.3.backup", false);
+            expect_rename(&mut adapter, ".4.3.staging", "4.3", false);
+
+            // Registration
+            adapter
+                .expect_path_is_file()
+                .with(predicate::eq(exec_path.clone()))
+                .returning(|_| true);
+            adapter
+                .expect_run_command_with_output()
+                .with(
+                    predicate::eq(exec_path.clone()),
+                    predicate::eq(vec!["--version".to_string()]),
+                    predicate::always(),
+                )
+                .times(1)
+                .returning(|_, _, _| Ok("4.3.stable.official.77dcf97d8\n".into()));
+            adapter
+                .expect_lock_file()
+                .with(lock_name("gdpm.cfg-"))
+                .times(1)
+                .returning(|_| Ok(Default::default()));
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(config_path))
+                .returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(config_path))
+                .returning(|_| Ok("".into()));
+            adapter
+                .expect_write_string_to_file()
+                .with(
+                    predicate::eq(config_path),
+                    predicate::function(|c: &str| c.contains("4-3 = ") && c.contains("77dcf97d8")),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            // Cleaning
+            expect_remove_dir(&mut adapter, ".4.3.backup");
+            adapter
+                .expect_remove_file()
+                .with(predicate::eq(Path::new(DOWNLOAD_ZIP)))
+                .times(1)
+                .returning(|_| Ok(()));
+
+            assert_eq!(install(&adapter).unwrap(), exec_path);
+        }
+
+        #[test]
+        fn test_install_from_official_zip_swap_failed() {
+            let mut adapter = install_adapter(true);
+            expect_rename(&mut adapter, "4.3", ".4.3.backup", false);
+            expect_rename(&mut adapter, ".4.3.staging", "4.3", true);
+
+            // Staging folder is removed and previous installation is restored
+            expect_remove_dir(&mut adapter, ".4.3.staging");
+            expect_rename(&mut adapter, ".4.3.backup", "4.3", false);
+
+            // Nothing is registered and the zip is kept
+            adapter
+                .expect_lock_file()
+                .with(lock_name("gdpm.cfg-"))
+                .never();
+            adapter.expect_remove_file().never();
+
+            assert!(matches!(
+                install(&adapter),
+                Err(EngineError::IoError(Error::RenameError(..)))
+            ));
+        }
+
+        #[test]
+        fn test_install_from_official_zip_backup_failed() {
+            let mut adapter = install_adapter(true);
+            expect_rename(&mut adapter, "4.3", ".4.3.backup", true);
+
+            // Previous installation is left untouched
+            expect_remove_dir(&mut adapter, ".4.3.staging");
+            adapter
+                .expect_lock_file()
+                .with(lock_name("gdpm.cfg-"))
+                .never();
+            adapter.expect_remove_file().never();
+
+            assert!(matches!(
+                install(&adapter),
+                Err(EngineError::IoError(Error::RenameError(..)))
+            ));
+        }
+
+        #[test]
+        fn test_install_from_official_zip_register_failed() {
+            let mut adapter = install_adapter(true);
+            let exec_path = engines_path("4.3").join("godot.x11");
+            expect_rename(&mut adapter, "4.3", ".4.3.backup", false);
+            expect_rename(&mut adapter, ".4.3.staging", "4.3", false);
+            adapter
+                .expect_path_is_file()
+                .with(predicate::eq(exec_path.clone()))
+                .returning(|_| true);
+            adapter
+                .expect_run_command_with_output()
+                .with(
+                    predicate::eq(exec_path),
+                    predicate::eq(vec!["--version".to_string()]),
+                    predicate::always(),
+                )
+                .times(1)
+                .returning(|_, _, _| Ok("4.3.stable.official.77dcf97d8\n".into()));
+            adapter
+                .expect_lock_file()
+                .with(lock_name("gdpm.cfg-"))
+                .times(1)
+                .returning(|p| Err(Error::LockFileError(p.into(), "busy".into())));
+
+            // New version is removed and previous installation is restored
+            expect_remove_dir(&mut adapter, "4.3");
+            expect_rename(&mut adapter, ".4.3.backup", "4.3", false);
+            adapter.expect_write_string_to_file().never();
+            adapter.expect_remove_file().never();
+
+            assert!(install(&adapter).is_err());
+        }
+
+        #[test]
+        fn test_install_from_official_zip_extract_failed() {
+            let mut adapter = MockIoAdapter::new();
+            let staging = engines_path(".4.3.staging");
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter
+                .expect_path_exists()
+                .with(predicate::in_iter([
+                    PathBuf::from("/home/user/.config/gdpm"),
+                    PathBuf::from(ENGINES_DIR),
+                ]))
+                .returning(|_| true);
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+            adapter
+                .expect_lock_file()
+                .with(lock_name("4.3-"))
+                .times(1)
+                .returning(|_| Ok(Default::default()));
+            adapter
+                .expect_path_exists()
+                .with(predicate::in_iter([
+                    staging.clone(),
+                    engines_path(".4.3.backup"),
+                ]))
+                .times(2)
+                .returning(|_| false);
+            adapter.expect_create_dir().times(2).returning(|_| Ok(()));
+            adapter
+                .expect_open_and_extract_zip()
+                .times(1)
+                .returning(|s, d| Err(Error::ExtractZipError(s.into(), d.into(), "broken".into())));
+
+            // Only the staging folder is removed
+            expect_remove_dir(&mut adapter, ".4.3.staging");
+            adapter.expect_rename().never();
+            adapter.expect_remove_file().never();
+
+            assert!(matches!(
+                install(&adapter),
+                Err(EngineError::IoError(Error::ExtractZipError(..)))
+            ));
+        }
+
+        #[test]
+        fn test_list_skips_invalid_entries() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                [engines]
+                1-0-0 = { "path": "/hello", "version": "1.0.0" }
+                broken = { "path": "/hi", "version": "2.0.0.unknown" }
+                missing = { "version": "3.0.0" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            let engines = ehandler.list().unwrap();
+            assert_eq!(engines.len(), 1);
+            assert_eq!(engines[0].path, PathBuf::from("/hello"));
+        }
+
+        #[test]
+        fn test_list_sorted_by_version() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                [engines]
+                4-10 = { "path": "/c", "version": "4.10" }
+                4-2 = { "path": "/a", "version": "4.2" }
+                4-3-rc1 = { "path": "/b", "version": "4.3.rc1" }
+                4-11-beta2 = { "path": "/d", "version": "4.11.beta2" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            let paths: Vec<_> = ehandler
+                .list()
+                .unwrap()
+                .into_iter()
+                .map(|e| e.path)
+                .collect();
+            assert_eq!(
+                paths,
+                vec![
+                    PathBuf::from("/a"),
+                    PathBuf::from("/b"),
+                    PathBuf::from("/c"),
+                    PathBuf::from("/d")
+                ]
+            );
+
+            let latest = ehandler.get_latest().unwrap().unwrap();
+            assert_eq!(latest.path, PathBuf::from("/c"));
+        }
+
+        #[test]
+        fn test_resolve() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                [engines]
+                4-2 = { "path": "/a", "version": "4.2" }
+                4-3 = { "path": "/b", "version": "4.3" }
+                4-4-beta1 = { "path": "/c", "version": "4.4.beta1" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            let resolve = |req: &str, pre_release| {
+                ehandler
+                    .resolve(
+                        &req.parse::<GodotVersionReq>()
+                            .unwrap()
+                            .with_pre_release(pre_release),
+                    )
+                    .map(|e| e.path)
+            };
+
+            assert_eq!(resolve("4", false).unwrap(), PathBuf::from("/b"));
+            assert_eq!(resolve("latest", true).unwrap(), PathBuf::from("/c"));
+            assert_eq!(resolve("4.2", false).unwrap(), PathBuf::from("/a"));
+            assert!(matches!(
+                resolve("^3.5", false),
+                Err(EngineError::NoMatchingEngine(_))
+            ));
+        }
+
+        #[test]
+        fn test_expand_alias() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                default_engine = "lts"
+
+                [aliases]
+                lts = "4.2"
+                loop = "lts"
+
+                [engines]
+                4-2 = { "path": "/a", "version": "4.2" }
+                4-2-1 = { "path": "/b", "version": "4.2.1" }
+                4-3 = { "path": "/c", "version": "4.3" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            let expand = |req: &str| ehandler.expand_alias(&req.parse().unwrap());
+
+            assert_eq!(expand("lts").unwrap().to_string(), "4.2");
+            assert_eq!(expand("lts.mono").unwrap().to_string(), "4.2.mono");
+            assert_eq!(expand("4.3").unwrap().to_string(), "4.3");
+            assert!(matches!(
+                expand("unknown"),
+                Err(EngineError::UnknownAlias(_))
+            ));
+            assert!(matches!(expand("loop"), Err(EngineError::InvalidAlias(..))));
+
+            assert_eq!(
+                ehandler.resolve(&"lts".parse().unwrap()).unwrap().path,
+                PathBuf::from("/a")
+            );
+            assert_eq!(
+                ehandler.get_default().unwrap(),
+                Some(GodotVersion::try_from("4.2").unwrap())
+            );
+            assert_eq!(ehandler.get_default_alias().unwrap(), Some("lts".into()));
+        }
+
+        #[test]
+        fn test_default_mono_alias() {
+            let mut adapter = config_adapter(indoc::indoc! {r#"
+                default_engine = "lts.mono"
+
+                [aliases]
+                lts = "4.2"
+
+                [engines]
+                4-2 = { "path": "/a", "version": "4.2" }
+                4-2-mono = { "path": "/b", "version": "4.2.mono" }
+            "#});
+            adapter.expect_path_is_file().returning(|_| true);
+            adapter
+                .expect_lock_file()
+                .returning(|_| Ok(Default::default()));
+            adapter
+                .expect_write_string_to_file()
+                .with(
+                    predicate::eq(PathBuf::from("/home/user/.config/gdpm/gdpm.cfg")),
+                    predicate::function(|c: &str| c.contains("\"lts.mono\"")),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let ehandler = EngineHandler::new(&adapter);
+            assert_eq!(
+                ehandler.get_default().unwrap(),
+                Some(GodotVersion::try_from("4.2.mono").unwrap())
+            );
+            assert_eq!(
+                ehandler.get_default_alias().unwrap(),
+                Some("lts.mono".into())
+            );
+            assert_eq!(ehandler.check().unwrap(), vec![]);
+
+            ehandler
+                .set_default_alias(&"lts.mono".parse().unwrap())
+                .unwrap();
+        }
+
+        #[test]
+        fn test_register() {
+            let mut adapter = MockIoAdapter::new();
+            let config_path = PathBuf::from("/home/user/.config/gdpm/gdpm.cfg");
+            let locked = Arc::new(AtomicBool::new(false));
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter.expect_path_exists().returning(|_| true);
+            adapter.expect_path_is_file().returning(|_| true);
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+            adapter.expect_lock_file().times(1).returning({
+                let locked = locked.clone();
+                move |_| {
+                    locked.store(true, Ordering::SeqCst);
+                    Ok(Default::default())
+                }
+            });
+            // Engines and default engine are read once the configuration is locked
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(config_path.clone()))
+                .returning(move |_| {
+                    assert!(locked.load(Ordering::SeqCst), "configuration is locked");
+                    Ok(indoc::indoc! {r#"
+                        default_engine = "lts"
+
+                        [aliases]
+                        lts = "4.3"
+
+                        [engines]
+                        4-2 = { "path": "/a", "version": "4.2" }
+                    "#}
+                    .into())
+                });
+            adapter
+                .expect_write_string_to_file()
+                .with(
+                    predicate::eq(config_path),
+                    predicate::function(|c: &str| {
+                        c.contains("4-2 = ")
+                            && c.contains("4-3 = ")
+                            && c.contains("default_engine = \"lts\"")
+                    }),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let entry = EngineInfo {
+                path: PathBuf::from("/b"),
+                version: GodotVersion::try_from("4.3").unwrap(),
+                hash: None,
+                last_used: None,
+            };
+            EngineHandler::new(&adapter).register(entry).unwrap();
+        }
+
+        #[test]
+        fn test_get_prune_candidates() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                default_engine = "4.1"
+
+                [engines]
+                4-1 = { "path": "/a", "version": "4.1", "last_used": 0 }
+                4-2 = { "path": "/b", "version": "4.2", "last_used": 99999999999 }
+                4-3 = { "path": "/c", "version": "4.3", "last_used": 0 }
+                4-4 = { "path": "/d", "version": "4.4", "last_used": 0 }
+                4-5 = { "path": "/e", "version": "4.5" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            let candidates = |referenced: Option<&[GodotVersion]>, unused_for| {
+                ehandler
+                    .get_prune_candidates(referenced, unused_for)
+                    .unwrap()
+                    .into_iter()
+                    .map(|e| e.path)
+                    .collect::<Vec<_>>()
+            };
+            let referenced = [GodotVersion::try_from("4.3").unwrap()];
+            let day = Duration::from_secs(24 * 60 * 60);
+
+            assert_eq!(
+                candidates(Some(&referenced), None),
+                vec![
+                    PathBuf::from("/b"),
+                    PathBuf::from("/d"),
+                    PathBuf::from("/e")
+                ]
+            );
+            assert_eq!(
+                candidates(None, Some(day)),
+                vec![PathBuf::from("/c"), PathBuf::from("/d")]
+            );
+            assert_eq!(
+                candidates(Some(&referenced), Some(day)),
+                vec![PathBuf::from("/d")]
+            );
+        }
+
+        #[test]
+        fn test_scan_engines() {
+            let root = tempfile::tempdir().unwrap();
+            for folder in ["a", "b", "c", "d", "e"] {
+                std::fs::create_dir(root.path().join(folder)).unwrap();
+                std::fs::write(root.path().join(folder).join("godot.exe"), "").unwrap();
+            }
+            let registered_path = root.path().join("d").join("godot.exe");
+            let config = format!(
+                "[engines]\n4-3 = {{ \"path\": \"/elsewhere/godot\", \"version\": \"4.3\" }}\n4-1 = {{ \"path\": \"{}\", \"version\": \"4.1\" }}\n",
+                registered_path.display()
+            );
+
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            adapter
+                .expect_path_exists()
+                .with(predicate::function(|p: &Path| p.starts_with("/home/user")))
+                .returning(|_| true);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(PathBuf::from(
+                    "/home/user/.config/gdpm/gdpm.cfg",
+                )))
+                .returning(move |_| Ok(config.clone()));
+            adapter
+                .expect_read_dir()
+                .returning(|p| DefaultIoAdapter.read_dir(p));
+            adapter
+                .expect_path_is_file()
+                .returning(|p| DefaultIoAdapter.path_is_file(p));
+            adapter
+                .expect_run_command_with_output()
+                .with(
+                    predicate::always(),
+                    predicate::eq(vec!["--version".to_string()]),
+                    predicate::eq(Duration::from_secs(10)),
+                )
+                .returning(|p, _, _| {
+                    let folder = p.parent().and_then(|f| f.file_name()).unwrap_or_default();
+                    match folder.to_str() {
+                        Some("a") | Some("b") => Ok("4.2.stable.official.46dc27791\n".into()),
+                        Some("c") => Ok("4.3.stable.official.77dcf97d8\n".into()),
+                        Some("d") => Ok("4.1.stable.official.970459615\n".into()),
+                        _ => Err(Error::CommandFailed(
+                            "godot".into(),
+                            "exit status: 1".into(),
+                        )),
+                    }
+                });
+
+            let entry = |version: &str, path: PathBuf, hash: Option<&str>| EngineInfo {
+                version: GodotVersion::try_from(version).unwrap(),
+                path,
+                hash: hash.map(Into::into),
+                last_used: None,
+            };
+
+            let ehandler = EngineHandler::new(&adapter);
+            let scanned = ehandler.scan_engines(root.path(), 1).unwrap();
+            let first = root.path().join("a").join("godot.exe");
+            assert_eq!(
+                scanned,
+                vec![
+                    ScannedEngine::New(entry("4.2", first.clone(), Some("46dc27791"))),
+                    ScannedEngine::Duplicate(
+                        root.path().join("b").join("godot.exe"),
+                        entry("4.2", first, Some("46dc27791"))
+                    ),
+                    ScannedEngine::Duplicate(
+                        root.path().join("c").join("godot.exe"),
+                        entry("4.3", "/elsewhere/godot".into(), None)
+                    ),
+                    ScannedEngine::AlreadyRegistered(entry("4.1", registered_path, None)),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_read_versions_from_cache() {
+            let adapter = file_adapter(
+                "remote-cache.cfg",
+                indoc::indoc! {r#"
+                updated_at = "99999999999"
+
+                [remote]
+                4.10 = { "version": "4.10", "kind": "stable", "mono": false }
+                4.2 = { "version": "4.2", "kind": "stable", "mono": false }
+                4.3.rc1 = { "version": "4.3", "kind": "rc1", "mono": false }
+            "#},
+            );
+
+            let ehandler = EngineHandler::new(&adapter);
+            let versions: Vec<_> = ehandler
+                .read_versions_from_cache(DownloadConfig::default().remote_cache_ttl)
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .map(|v| v.to_string())
+                .collect();
+            assert_eq!(versions, vec!["4.2", "4.3.rc1", "4.10"]);
+        }
+
+        #[test]
+        fn test_read_versions_from_cache_malformed() {
+            let adapter = file_adapter("remote-cache.cfg", "updated_at = \"99999999999\n[remote");
+
+            let ehandler = EngineHandler::new(&adapter);
+            assert_eq!(
+                ehandler
+                    .read_versions_from_cache(DownloadConfig::default().remote_cache_ttl)
+                    .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn test_read_versions_from_cache_expired() {
+            let adapter = file_adapter(
+                "remote-cache.cfg",
+                indoc::indoc! {r#"
+                updated_at = "0"
+
+                [remote]
+                4.2 = { "version": "4.2", "kind": "stable", "mono": false }
+            "#},
+            );
+
+            let ehandler = EngineHandler::new(&adapter);
+            assert_eq!(
+                ehandler
+                    .read_versions_from_cache(DownloadConfig::default().remote_cache_ttl)
+                    .unwrap(),
+                None
+            );
+        }
+
+        fn extract_templates_adapter() -> MockIoAdapter {
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(|| Ok(PathBuf::from("/home/user/.config")));
+            // Previous templates, and folders from an interrupted installation are present
+            adapter
+                .expect_path_exists()
+                .with(predicate::function(|p: &Path| p.starts_with("/home/user")))
+                .returning(|_| true);
+            adapter
+                .expect_create_dir()
+                .with(predicate::eq(PathBuf::from(
+                    "/home/user/.config/Godot/export_templates/.4.3.stable.staging",
+                )))
+                .times(1)
+                .returning(|_| Ok(()));
+            adapter.expect_canonicalize().returning(|p| p.to_owned());
+            adapter
+                .expect_lock_file()
+                .with(predicate::function(|p: &Path| {
+                    p.starts_with("/home/user/.config/gdpm/locks")
+                        && p.file_name()
+                            .is_some_and(|n| n.to_string_lossy().starts_with("4.3.stable-"))
+                }))
+                .times(1)
+                .returning(|_| Ok(Default::default()));
+            adapter
+        }
+
+        #[test]
+        fn test_extract_export_templates() {
+            let templates = Path::new("/home/user/.config/Godot/export_templates");
+            let staging = templates.join(".4.3.stable.staging");
+            let mut adapter = extract_templates_adapter();
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(staging.clone()))
+                .times(1)
+                .returning(|_| Ok(()));
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(templates.join(".4.3.stable.backup")))
+                .times(2)
+                .returning(|_| Ok(()));
+            let mut seq = mockall::Sequence::new();
+            adapter
+                .expect_open_and_extract_zip()
+                .with(
+                    predicate::eq(PathBuf::from("/tmp/templates.tpz")),
+                    predicate::eq(staging.clone()),
+                )
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_, _| Ok(()));
+            adapter
+                .expect_move_files_in_parent_folder()
+                .with(predicate::eq(staging.join("templates")))
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_| Ok(()));
+            adapter
+                .expect_rename()
+                .with(
+                    predicate::eq(templates.join("4.3.stable")),
+                    predicate::eq(templates.join(".4.3.stable.backup")),
+                )
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_, _| Ok(()));
+            adapter
+                .expect_rename()
+                .with(
+                    predicate::eq(staging.clone()),
+                    predicate::eq(templates.join("4.3.stable")),
+                )
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_, _| Ok(()));
+
+            let ehandler = EngineHandler::new(&adapter);
+            let path = ehandler
+                .extract_export_templates(
+                    Path::new("/tmp/templates.tpz"),
+                    GodotVersion::try_from("4.3").unwrap(),
+                )
+                .unwrap();
+            assert_eq!(path, templates.join("4.3.stable"));
+        }
+
+        #[test]
+        fn test_extract_export_templates_keeps_previous_on_failure() {
+            let templates = Path::new("/home/user/.config/Godot/export_templates");
+            let mut adapter = extract_templates_adapter();
+            adapter
+                .expect_open_and_extract_zip()
+                .returning(|s, d| Err(Error::ExtractZipError(s.into(), d.into(), "broken".into())));
+            // Previous templates are not touched
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(templates.join(".4.3.stable.staging")))
+                .times(2)
+                .returning(|_| Ok(()));
+            adapter
+                .expect_remove_dir_all()
+                .with(predicate::eq(templates.join(".4.3.stable.backup")))
+                .times(1)
+                .returning(|_| Ok(()));
+            adapter.expect_rename().never();
+
+            let ehandler = EngineHandler::new(&adapter);
+            assert!(matches!(
+                ehandler.extract_export_templates(
+                    Path::new("/tmp/templates.tpz"),
+                    GodotVersion::try_from("4.3").unwrap(),
+                ),
+                Err(EngineError::IoError(Error::ExtractZipError(..)))
+            ));
+        }
+
+        #[test]
+        fn test_verify_export_templates() {
+            // Folder listings cannot be mocked, only listed folders are created
+            let root = tempfile::tempdir().unwrap();
+            let config_dir = root.path().to_owned();
+            let templates = root.path().join("Godot").join("export_templates");
+            let file = |p: &str| templates.join(p);
+            std::fs::create_dir_all(file("4.3.stable")).unwrap();
+            std::fs::create_dir_all(file("4.2.stable.mono")).unwrap();
+            for name in [
+                "4.3.stable/version.txt",
+                "4.3.stable/linux_release.x86_64",
+                "4.2.stable.mono/version.txt",
+            ] {
+                std::fs::write(file(name), "").unwrap();
+            }
+
+            let mut adapter = MockIoAdapter::new();
+            adapter
+                .expect_get_user_configuration_directory()
+                .returning(move || Ok(config_dir.clone()));
+            adapter
+                .expect_path_exists()
+                .with(predicate::in_iter([
+                    root.path().join("Godot"),
+                    templates.clone(),
+                    file("4.3.stable"),
+                    file("4.2.stable.mono"),
+                ]))
+                .returning(|_| true);
+            adapter
+                .expect_path_exists()
+                .with(predicate::eq(file("4.1.stable")))
+                .times(1)
+                .returning(|_| false);
+            adapter
+                .expect_read_dir()
+                .with(predicate::in_iter([
+                    templates.clone(),
+                    file("4.3.stable"),
+                    file("4.2.stable.mono"),
+                ]))
+                .returning(|p| DefaultIoAdapter.read_dir(p));
+            adapter
+                .expect_path_is_file()
+                .with(predicate::in_iter([
+                    file("4.3.stable/version.txt"),
+                    file("4.3.stable/linux_release.x86_64"),
+                    file("4.2.stable.mono/version.txt"),
+                ]))
+                .returning(|_| true);
+            adapter
+                .expect_path_is_file()
+                .with(predicate::in_iter([
+                    file("4.3.stable"),
+                    file("4.2.stable.mono"),
+                ]))
+                .returning(|_| false);
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(file("4.3.stable/version.txt")))
+                .times(1)
+                .returning(|_| Ok("4.3.stable\n".into()));
+            adapter
+                .expect_read_file_to_string()
+                .with(predicate::eq(file("4.2.stable.mono/version.txt")))
+                .times(1)
+                .returning(|_| Ok("4.2.rc1.mono".into()));
+
+            let ehandler = EngineHandler::new(&adapter);
+            let version = |s| GodotVersion::try_from(s).unwrap();
+
+            let installed: Vec<_> = ehandler
+                .list_export_templates()
+                .unwrap()
+                .into_iter()
+                .map(|(v, _)| v.to_string())
+                .collect();
+            assert_eq!(installed, vec!["4.2.mono", "4.3"]);
+
+            assert_eq!(
+                ehandler.verify_export_templates(&version("4.3")).unwrap(),
+                vec![]
+            );
+            assert_eq!(
+                ehandler
+                    .verify_export_templates(&version("4.2.mono"))
+                    .unwrap(),
+                vec![
+                    ExportTemplatesIssue::VersionMismatch(
+                        "4.2.stable.mono".into(),
+                        "4.2.rc1.mono".into()
+                    ),
+                    ExportTemplatesIssue::NoTemplates
+                ]
+            );
+            assert!(matches!(
+                ehandler.verify_export_templates(&version("4.1")),
+                Err(EngineError::ExportTemplatesNotInstalled(_))
+            ));
+        }
+
+        #[test]
+        #[cfg(not(windows))]
+        fn test_is_engine_candidate() {
+            for name in [
+                "Godot",
+                "godot",
+                "Godot_v4.3-stable_linux.x86_64",
+                "Godot_v3.6-stable_mono_x11.64",
+                "Godot_v4.3-stable_macos.universal",
+            ] {
+                assert!(is_engine_candidate(name), "{}", name);
+            }
+
+            for name in [
+                "Godot_v4.3-stable_export_templates.tpz",
+                "Godot_v4.3-stable_linux.x86_64.zip",
+                "GodotSharp.dll",
+                "godot.cmd",
+                "Godot_v4.3-stable_win64_console.exe",
+                "README.md",
+            ] {
+                assert!(!is_engine_candidate(name), "{}", name);
+            }
+        }
+
+        #[test]
+        fn test_get_default_stale() {
+            let adapter = config_adapter(indoc::indoc! {r#"
+                default_engine = "2.0.0"
+
+                [engines]
+                1-0-0 = { "path": "/hello", "version": "1.0.0" }
+            "#});
+
+            let ehandler = EngineHandler::new(&adapter);
+            assert_eq!(ehandler.get_default().unwrap(), None);
+        }
+
+        #[test]
+        fn test_check() {
+            let mut adapter = config_adapter(indoc::indoc! {r#"
+                default_engine = "2.0.0"
+
+                [engines]
+                1-0-0 = { "path": "/hello", "version": "1.0.0" }
+                2-0-0 = { "path": "/missing", "version": "2.0.0" }
+                broken = { "path": "/hi", "version": "2.0.0.unknown" }
+            "#});
+            adapter
+                .expect_path_is_file()
+                .returning(|p| p == Path::new("/hello"));
+
+            let ehandler = EngineHandler::new(&adapter);
+            let issues = ehandler.check().unwrap();
+            assert_eq!(
+                issues,
+                vec![
+                    EngineConfigIssue::MissingExecutable("2-0-0".into(), PathBuf::from("/missing")),
+                    EngineConfigIssue::InvalidEntry(
+                        "broken".into(),
+                        "Wrong version kind: unknown".into()
+                    ),
+                    EngineConfigIssue::InvalidDefault("2.0.0".into()),
+                ]
+            );
+        }
+    }
 }
@@ -2,7 +2,8 @@
 
 use std::path::PathBuf;
 
-use gdpm_types::version::GodotVersion;
+use gdpm_downloader::error::DownloadError;
+use gdpm_types::version::{GodotVersion, GodotVersionReq};
 use gdsettings_parser::{GdSettingsError, ParserError};
 
 /// Config error
@@ -11,8 +12,12 @@ use gdsettings_parser::{GdSettingsError, ParserError};
 pub enum ConfigError {
     #[error("Incomplete settings.")]
     IncompleteSettings(#[from] GdSettingsError),
-    #[error("Malformed settings.")]
+    #[error("Malformed settings file.")]
     MalformedSettings(#[from] ParserError),
+    #[error("Invalid setting '{0}', expected {1}.")]
+    InvalidSetting(String, String),
+    #[error(transparent)]
+    InvalidDownloadSettings(#[from] DownloadError),
     #[error(transparent)]
     IoError(#[from] gdpm_io::Error),
 }
@@ -27,6 +32,20 @@ pub enum EngineError {
     EngineMissingFromPath(GodotVersion, PathBuf),
     #[error("Engine version '{0}' is not installed.")]
     EngineNotInstalled(GodotVersion),
+    #[error("Export templates for version '{0}' are not installed.")]
+    ExportTemplatesNotInstalled(GodotVersion),
+    #[error("Unknown engine alias '{0}', add it to the [aliases] section of gdpm.cfg.")]
+    UnknownAlias(String),
+    #[error("Engine alias '{0}' points to another alias ('{1}').")]
+    InvalidAlias(String, String),
+    #[error("No installed engine version matches '{0}'.")]
+    NoMatchingEngine(GodotVersionReq),
+    #[error("Invalid engine version from {0}: {1}")]
+    InvalidPinnedVersion(String, #[source] gdpm_types::version::Error),
+    #[error("Could not detect engine version from '{0}' (output: '{1}').")]
+    VersionDetectionFailed(PathBuf, String),
+    #[error("Invalid engine entry: {0}")]
+    InvalidEngineEntry(String),
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
     #[error(transparent)]
@@ -41,10 +60,16 @@ pub enum EngineError {
 pub enum ProjectError {
     #[error("Project not found at path '{0}'.")]
     ProjectNotFound(String),
-    #[error("Malformed project.")]
+    #[error("Malformed project file.")]
     MalformedProject(#[source] ParserError),
     #[error("Missing project property '{0}'.")]
     MissingProperty(String),
+    #[error("Invalid project setting key '{0}'.")]
+    InvalidSettingKey(String),
+    #[error("Invalid project version '{0}'.")]
+    InvalidVersion(String),
+    #[error("Export presets not found at path '{0}'.")]
+    ExportPresetsNotFound(String),
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
     #[error(transparent)]
@@ -53,6 +78,28 @@ pub enum ProjectError {
     VersionError(#[from] gdpm_types::version::Error),
 }
 
+/// Export error
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum ExportError {
+    #[error("Export preset '{0}' is not found.")]
+    PresetNotFound(String),
+    #[error("Malformed export preset '{0}'.")]
+    MalformedPreset(String),
+    #[error("Export preset '{0}' has no export path.")]
+    MissingExportPath(String),
+    #[error("Export templates for version '{0}' are not installed at path '{1}'.")]
+    MissingExportTemplates(GodotVersion, PathBuf),
+    #[error("Export of preset '{0}' failed ({1}).")]
+    ExportFailed(String, String),
+    #[error(transparent)]
+    ProjectError(#[from] ProjectError),
+    #[error(transparent)]
+    EngineError(#[from] EngineError),
+    #[error(transparent)]
+    IoError(#[from] gdpm_io::Error),
+}
+
 /// Plugin error
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -73,6 +120,22 @@ pub enum PluginError {
     IoError(#[from] gdpm_io::Error),
 }
 
+/// Build error
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum BuildError {
+    #[error("Invalid build name '{0}'.")]
+    InvalidName(String),
+    #[error("Could not read engine version from '{0}'.")]
+    MissingVersionInfo(PathBuf),
+    #[error("No editor binary found in '{0}'.")]
+    MissingEditorBinary(PathBuf),
+    #[error(transparent)]
+    EngineError(#[from] EngineError),
+    #[error(transparent)]
+    IoError(#[from] gdpm_io::Error),
+}
+
 impl From<GdSettingsError> for EngineError {
     fn from(e: GdSettingsError) -> Self {
         EngineError::ConfigError(ConfigError::IncompleteSettings(e))
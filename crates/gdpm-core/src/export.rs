@@ -0,0 +1,485 @@
+//! Export module.
+
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use gdpm_io::{Error, IoAdapter};
+use gdpm_types::version::GodotVersion;
+use gdsettings_parser::GdSettings;
+use tracing::debug;
+
+use crate::{
+    config::{ExportPresetsConfig, GodotDir},
+    engine::EngineHandler,
+    error::ExportError,
+};
+
+/// Export mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportMode {
+    /// Release export
+    Release,
+    /// Debug export
+    Debug,
+}
+
+impl ExportMode {
+    /// Get engine command line arguments to export a preset.
+    pub fn get_engine_args(&self, is_version_4: bool, preset: &str, output: &Path) -> Vec<String> {
+        let mut args = vec![];
+
+        if is_version_4 {
+            args.push("--headless".to_string());
+            args.push(match self {
+                Self::Release => "--export-release".into(),
+                Self::Debug => "--export-debug".into(),
+            });
+        } else {
+            args.push("--no-window".to_string());
+            args.push(match self {
+                Self::Release => "--export".into(),
+                Self::Debug => "--export-debug".into(),
+            });
+        }
+
+        args.push(preset.to_string());
+        args.push(output.to_string_lossy().to_string());
+        args
+    }
+}
+
+impl std::fmt::Display for ExportMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Release => write!(f, "release"),
+            Self::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+/// Export preset
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPreset {
+    /// Preset index in file
+    pub index: usize,
+    /// Preset name
+    pub name: String,
+    /// Target platform
+    pub platform: String,
+    /// Export path, relative to the project
+    pub export_path: Option<String>,
+    /// Is the preset runnable?
+    pub runnable: bool,
+    /// Include filters
+    pub include_filter: Vec<String>,
+    /// Exclude filters
+    pub exclude_filter: Vec<String>,
+}
+
+impl ExportPreset {
+    /// Extract export presets from settings, ordered by index
+    pub fn from_settings(settings: &GdSettings) -> Result<Vec<Self>, ExportError> {
+        let mut presets = vec![];
+
+        for (section_name, section) in settings.get_map() {
+            let index = match section_name
+                .strip_prefix("preset.")
+                .and_then(|x| x.parse::<usize>().ok())
+            {
+                Some(i) => i,
+                // Skip options sections
+                None => continue,
+            };
+
+            let name = section
+                .get("name")
+                .and_then(|x| x.to_str())
+                .ok_or_else(|| ExportError::MalformedPreset(section_name.clone()))?;
+            let platform = section
+                .get("platform")
+                .and_then(|x| x.to_str())
+                .ok_or_else(|| ExportError::MalformedPreset(name.clone()))?;
+            let export_path = section
+                .get("export_path")
+                .and_then(|x| x.to_str())
+                .filter(|x| !x.is_empty());
+            let runnable = section
+                .get("runnable")
+                .and_then(|x| x.to_bool())
+                .unwrap_or(false);
+            let include_filter = section
+                .get("include_filter")
+                .and_then(|x| x.to_str())
+                .map(|x| split_filter(&x))
+                .unwrap_or_default();
+            let exclude_filter = section
+                .get("exclude_filter")
+                .and_then(|x| x.to_str())
+                .map(|x| split_filter(&x))
+                .unwrap_or_default();
+
+            presets.push(Self {
+                index,
+                name,
+                platform,
+                export_path,
+                runnable,
+                include_filter,
+                exclude_filter,
+            });
+        }
+
+        presets.sort_by_key(|p| p.index);
+        Ok(presets)
+    }
+
+    /// Get verbose name
+    pub fn get_verbose_name(&self) -> String {
+        format!(
+            "{} ({}) -> {}",
+            self.name.color("green"),
+            self.platform.color("blue"),
+            self.export_path
+                .as_deref()
+                .unwrap_or("no export path")
+                .color("yellow")
+        )
+    }
+}
+
+fn split_filter(filter: &str) -> Vec<String> {
+    filter
+        .split(',')
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+        .collect()
+}
+
+/// Export handler.
+pub struct ExportHandler<'a, I: IoAdapter> {
+    io_adapter: &'a I,
+}
+
+impl<'a, I: IoAdapter> ExportHandler<'a, I> {
+    /// Creates a new export handler.
+    pub fn new(io_adapter: &'a I) -> Self {
+        Self { io_adapter }
+    }
+
+    /// List project export presets.
+    pub fn list_presets(&self, path: &Path) -> Result<Vec<ExportPreset>, ExportError> {
+        let econf = ExportPresetsConfig::new(self.io_adapter);
+        let conf = econf.load(path)?;
+        ExportPreset::from_settings(&conf)
+    }
+
+    /// Get project export preset from its name.
+    pub fn get_preset(&self, path: &Path, name: &str) -> Result<ExportPreset, ExportError> {
+        self.list_presets(path)?
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| ExportError::PresetNotFound(name.into()))
+    }
+
+    /// Ensure export templates are installed for a version.
+    pub fn ensure_export_templates(&self, version: &GodotVersion) -> Result<PathBuf, ExportError> {
+        let gdir = GodotDir::new(self.io_adapter);
+        let templates_path = gdir.get_specific_export_templates_directory(version)?;
+        if !self.io_adapter.path_exists(&templates_path) {
+            return Err(ExportError::MissingExportTemplates(
+                version.clone(),
+                templates_path,
+            ));
+        }
+
+        Ok(templates_path)
+    }
+
+    /// Export a preset using an engine version.
+    ///
+    /// Returns the exported file path.
+    pub fn export_preset(
+        &self,
+        version: &GodotVersion,
+        path: &Path,
+        preset: &ExportPreset,
+        mode: ExportMode,
+    ) -> Result<PathBuf, ExportError> {
+        let export_path = preset
+            .export_path
+            .as_ref()
+            .ok_or_else(|| ExportError::MissingExportPath(preset.name.clone()))?;
+        let output = path.join(export_path);
+
+        self.ensure_export_templates(version)?;
+        if let Some(parent) = output.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        let ehandler = EngineHandler::new(self.io_adapter);
+        let engine = ehandler.mark_as_used(version)?;
+        let args =
+            mode.get_engine_args(engine.is_version_4(), &preset.name, Path::new(export_path));
+
+        debug!(
+            "Exporting preset '{}' using engine '{}' ...",
+            preset.name.color("green"),
+            version.to_string().color("green")
+        );
+
+        // Export path is relative to the project folder
+        let engine_path = self.io_adapter.canonicalize(&engine.path);
+        let args: Vec<_> = ["--path".to_string(), ".".to_string()]
+            .into_iter()
+            .chain(args)
+            .collect();
+        match self
+            .io_adapter
+            .run_command(&engine_path.to_string_lossy(), &args, path)
+        {
+            Err(Error::CommandFailed(_, status)) => {
+                return Err(ExportError::ExportFailed(preset.name.clone(), status))
+            }
+            r => r?,
+        }
+
+        Ok(output)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<(), ExportError> {
+        let mut missing: Vec<_> = path
+            .ancestors()
+            .take_while(|p| !p.as_os_str().is_empty() && !self.io_adapter.path_exists(p))
+            .collect();
+        missing.reverse();
+
+        for dir in missing {
+            self.io_adapter.create_dir(dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use gdpm_io::{Error, MockIoAdapter};
+    use gdsettings_parser::GdSettings;
+    use mockall::predicate;
+
+    use crate::{
+        error::ExportError,
+        export::{ExportHandler, ExportMode, ExportPreset},
+    };
+
+    const PRESETS: &str = indoc::indoc! {r#"
+        [preset.0]
+        name="Windows Desktop"
+        platform="Windows Desktop"
+        runnable=true
+        export_filter="all_resources"
+        include_filter="*.json, data/*"
+        exclude_filter=""
+        export_path="build/windows/game.exe"
+
+        [preset.0.options]
+        application/file_version=""
+
+        [preset.1]
+        name="Web"
+        platform="Web"
+        runnable=false
+        export_path=""
+    "#};
+
+    #[test]
+    fn test_from_settings() {
+        let settings: GdSettings = PRESETS.parse().unwrap();
+        let presets = ExportPreset::from_settings(&settings).unwrap();
+
+        assert_eq!(
+            presets,
+            vec![
+                ExportPreset {
+                    index: 0,
+                    name: "Windows Desktop".into(),
+                    platform: "Windows Desktop".into(),
+                    export_path: Some("build/windows/game.exe".into()),
+                    runnable: true,
+                    include_filter: vec!["*.json".into(), "data/*".into()],
+                    exclude_filter: vec![],
+                },
+                ExportPreset {
+                    index: 1,
+                    name: "Web".into(),
+                    platform: "Web".into(),
+                    export_path: None,
+                    runnable: false,
+                    include_filter: vec![],
+                    exclude_filter: vec![],
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_preset() {
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/export_presets.cfg")))
+            .returning(|_| true);
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/export_presets.cfg")))
+            .returning(|_| Ok(PRESETS.into()));
+
+        let ehandler = ExportHandler::new(&adapter);
+        assert_eq!(ehandler.get_preset(Path::new("/"), "Web").unwrap().index, 1);
+        assert!(matches!(
+            ehandler.get_preset(Path::new("/"), "Android"),
+            Err(ExportError::PresetNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_export_templates() {
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_get_user_configuration_directory()
+            .returning(|| Ok(PathBuf::from("/home/user/.config")));
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/home/user/.config/Godot")))
+            .returning(|_| true);
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new(
+                "/home/user/.config/Godot/export_templates",
+            )))
+            .returning(|_| true);
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new(
+                "/home/user/.config/Godot/export_templates/4.3.stable",
+            )))
+            .returning(|_| false);
+
+        let ehandler = ExportHandler::new(&adapter);
+        assert!(matches!(
+            ehandler.ensure_export_templates(&"4.3".parse().unwrap()),
+            Err(ExportError::MissingExportTemplates(_, _))
+        ));
+    }
+
+    fn export_adapter() -> MockIoAdapter {
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_get_user_configuration_directory()
+            .returning(|| Ok(PathBuf::from("/home/user/.config")));
+        adapter.expect_path_exists().returning(|_| true);
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/home/user/.config/gdpm/gdpm.cfg")))
+            .returning(|_| {
+                Ok(indoc::indoc! {r#"
+                    [engines]
+                    4-3 = { "path": "/engines/godot", "version": "4.3" }
+                "#}
+                .into())
+            });
+        adapter.expect_canonicalize().returning(|p| p.to_owned());
+        adapter
+            .expect_lock_file()
+            .returning(|_| Ok(Default::default()));
+        adapter
+            .expect_write_string_to_file()
+            .returning(|_, _| Ok(()));
+        adapter
+    }
+
+    #[test]
+    fn test_export_preset() {
+        let settings: GdSettings = PRESETS.parse().unwrap();
+        let presets = ExportPreset::from_settings(&settings).unwrap();
+        let version = "4.3".parse().unwrap();
+
+        let mut adapter = export_adapter();
+        adapter
+            .expect_run_command()
+            .with(
+                predicate::eq("/engines/godot"),
+                predicate::eq(vec![
+                    "--path".to_string(),
+                    ".".into(),
+                    "--headless".into(),
+                    "--export-release".into(),
+                    "Windows Desktop".into(),
+                    "build/windows/game.exe".into(),
+                ]),
+                predicate::eq(Path::new("/project")),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let xhandler = ExportHandler::new(&adapter);
+        assert_eq!(
+            xhandler
+                .export_preset(
+                    &version,
+                    Path::new("/project"),
+                    &presets[0],
+                    ExportMode::Release
+                )
+                .unwrap(),
+            PathBuf::from("/project/build/windows/game.exe")
+        );
+        assert!(matches!(
+            xhandler.export_preset(
+                &version,
+                Path::new("/project"),
+                &presets[1],
+                ExportMode::Release
+            ),
+            Err(ExportError::MissingExportPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_preset_failed() {
+        let settings: GdSettings = PRESETS.parse().unwrap();
+        let presets = ExportPreset::from_settings(&settings).unwrap();
+
+        let mut adapter = export_adapter();
+        adapter
+            .expect_run_command()
+            .times(1)
+            .returning(|p, _, _| Err(Error::CommandFailed(p.into(), "exit status: 1".into())));
+
+        let xhandler = ExportHandler::new(&adapter);
+        assert!(matches!(
+            xhandler.export_preset(
+                &"4.3".parse().unwrap(),
+                Path::new("/project"),
+                &presets[0],
+                ExportMode::Debug
+            ),
+            Err(ExportError::ExportFailed(name, status))
+                if name == "Windows Desktop" && status == "exit status: 1"
+        ));
+    }
+
+    #[test]
+    fn test_get_engine_args() {
+        assert_eq!(
+            ExportMode::Release.get_engine_args(true, "Web", Path::new("build/index.html")),
+            vec!["--headless", "--export-release", "Web", "build/index.html"]
+        );
+        assert_eq!(
+            ExportMode::Debug.get_engine_args(false, "Web", Path::new("build/index.html")),
+            vec!["--no-window", "--export-debug", "Web", "build/index.html"]
+        );
+    }
+}
@@ -2,15 +2,19 @@
 
 #![warn(missing_docs)]
 
+pub mod build;
 pub mod config;
 pub mod engine;
 pub mod error;
+pub mod export;
+pub mod pin;
 pub mod plugins;
 pub mod project;
+pub mod shim;
 
 pub mod io {
     //! IO module.
-    pub use gdpm_io::{write_stderr, write_stdout, DefaultIoAdapter, IoAdapter};
+    pub use gdpm_io::{write_stderr, write_stdout, DefaultIoAdapter, Error, FileLock, IoAdapter};
 }
 
 pub mod downloader {
@@ -18,6 +22,11 @@ pub mod downloader {
     pub use gdpm_downloader::*;
 }
 
+pub mod parser {
+    //! Parser module.
+    pub use gdsettings_parser::*;
+}
+
 pub mod types {
     //! Types module.
     pub use gdpm_types::*;
@@ -0,0 +1,224 @@
+//! Engine pinning module.
+//!
+//! The engine version used for a project is looked up in this order:
+//!
+//! 1. the `--engine` command line argument,
+//! 2. the `GDPM_ENGINE` environment variable,
+//! 3. the nearest `.godot-version` file, searched upward from the project path,
+//! 4. the `engine/version` property of `project.godot`,
+//! 5. the default engine.
+//!
+//! Steps 2 to 4 are handled by [`PinHandler`].
+
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use gdpm_io::IoAdapter;
+use gdpm_types::version::GodotVersionReq;
+use tracing::debug;
+
+use crate::{error::EngineError, project::ProjectHandler};
+
+/// Engine version file name.
+pub const VERSION_FILE_NAME: &str = ".godot-version";
+/// Engine version environment variable.
+pub const ENGINE_ENV_VAR: &str = "GDPM_ENGINE";
+
+/// Source of an engine version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineSource {
+    /// Command line argument
+    Argument,
+    /// `GDPM_ENGINE` environment variable
+    Environment,
+    /// `.godot-version` file
+    VersionFile(PathBuf),
+    /// `engine/version` property from `project.godot`
+    Project,
+    /// Default engine
+    Default,
+}
+
+impl Display for EngineSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Argument => write!(f, "command line argument"),
+            Self::Environment => write!(f, "environment variable '{}'", ENGINE_ENV_VAR),
+            Self::VersionFile(p) => write!(f, "file '{}'", p.display()),
+            Self::Project => write!(f, "project file"),
+            Self::Default => write!(f, "default engine"),
+        }
+    }
+}
+
+/// Pin handler.
+pub struct PinHandler<'a, I: IoAdapter> {
+    io_adapter: &'a I,
+}
+
+impl<'a, I: IoAdapter> PinHandler<'a, I> {
+    /// Creates a new pin handler.
+    pub fn new(io_adapter: &'a I) -> Self {
+        Self { io_adapter }
+    }
+
+    /// Get pinned engine version for a project path, from environment,
+    /// version file or project file.
+    pub fn get_pinned_version(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(GodotVersionReq, EngineSource)>, EngineError> {
+        let env_value = std::env::var(ENGINE_ENV_VAR)
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        self.get_pinned_version_with_env(env_value, path)
+    }
+
+    /// Get pinned engine version for a project path, from version file or project file only.
+    pub fn get_project_pinned_version(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(GodotVersionReq, EngineSource)>, EngineError> {
+        self.get_pinned_version_with_env(None, path)
+    }
+
+    fn get_pinned_version_with_env(
+        &self,
+        env_value: Option<String>,
+        path: &Path,
+    ) -> Result<Option<(GodotVersionReq, EngineSource)>, EngineError> {
+        if let Some(value) = env_value {
+            let source = EngineSource::Environment;
+            return Ok(Some((parse_version_req(&value, &source)?, source)));
+        }
+
+        if let Some(file) = self.find_version_file(path) {
+            let contents = self.io_adapter.read_file_to_string(&file)?;
+            let source = EngineSource::VersionFile(file);
+            return Ok(Some((parse_version_req(&contents, &source)?, source)));
+        }
+
+        let phandler = ProjectHandler::new(self.io_adapter);
+        match phandler.get_project_info(path) {
+            Ok(info) => Ok(info
+                .get_engine_version()
+                .map(|v| (v.clone(), EngineSource::Project))),
+            Err(e) => {
+                debug!("No project info found at '{}': {}", path.display(), e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Find the nearest `.godot-version` file, searched upward from a path.
+    pub fn find_version_file(&self, path: &Path) -> Option<PathBuf> {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+        path.ancestors()
+            .map(|p| p.join(VERSION_FILE_NAME))
+            .find(|p| self.io_adapter.path_is_file(p))
+    }
+}
+
+fn parse_version_req(value: &str, source: &EngineSource) -> Result<GodotVersionReq, EngineError> {
+    let value = value.lines().next().unwrap_or_default().trim();
+    GodotVersionReq::from_str(value)
+        .map_err(|e| EngineError::InvalidPinnedVersion(source.to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use gdpm_io::MockIoAdapter;
+    use gdpm_types::version::GodotVersionReq;
+    use mockall::predicate;
+
+    use super::{EngineSource, PinHandler};
+    use crate::{engine::EngineHandler, error::EngineError};
+
+    fn version_file_adapter(contents: &'static str) -> MockIoAdapter {
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_path_is_file()
+            .returning(|p| p == Path::new("/home/user/game/.godot-version"));
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/home/user/game/.godot-version")))
+            .returning(move |_| Ok(contents.into()));
+        adapter
+    }
+
+    #[test]
+    fn test_find_version_file() {
+        let adapter = version_file_adapter("4.3\n");
+        let phandler = PinHandler::new(&adapter);
+
+        assert_eq!(
+            phandler.find_version_file(Path::new("/home/user/game/scenes/levels")),
+            Some(PathBuf::from("/home/user/game/.godot-version"))
+        );
+        assert_eq!(phandler.find_version_file(Path::new("/home/user")), None);
+    }
+
+    #[test]
+    fn test_get_pinned_version() {
+        let adapter = version_file_adapter("4.x.mono\n");
+        let phandler = PinHandler::new(&adapter);
+
+        assert_eq!(
+            phandler
+                .get_pinned_version_with_env(None, Path::new("/home/user/game/scenes"))
+                .unwrap(),
+            Some((
+                "4.x.mono".parse::<GodotVersionReq>().unwrap(),
+                EngineSource::VersionFile(PathBuf::from("/home/user/game/.godot-version"))
+            ))
+        );
+        assert_eq!(
+            phandler
+                .get_pinned_version_with_env(Some("3.6".into()), Path::new("/home/user/game"))
+                .unwrap(),
+            Some((
+                "3.6".parse::<GodotVersionReq>().unwrap(),
+                EngineSource::Environment
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_pinned_version_invalid() {
+        let mut adapter = version_file_adapter("four");
+        adapter
+            .expect_get_user_configuration_directory()
+            .returning(|| Ok(PathBuf::from("/home/user/.config")));
+        adapter
+            .expect_path_exists()
+            .with(predicate::function(|p: &Path| p.starts_with("/home/user")))
+            .returning(|_| true);
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/home/user/.config/gdpm/gdpm.cfg")))
+            .returning(|_| Ok("".into()));
+        let phandler = PinHandler::new(&adapter);
+
+        // Unknown words are parsed as aliases, which fail to resolve
+        let (req, _) = phandler
+            .get_pinned_version_with_env(None, Path::new("/home/user/game"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(req.alias(), Some("four"));
+        assert!(matches!(
+            EngineHandler::new(&adapter).resolve(&req),
+            Err(EngineError::UnknownAlias(name)) if name == "four"
+        ));
+
+        let adapter = version_file_adapter("four?");
+        let phandler = PinHandler::new(&adapter);
+        assert!(phandler
+            .get_pinned_version_with_env(None, Path::new("/home/user/game"))
+            .is_err());
+    }
+}
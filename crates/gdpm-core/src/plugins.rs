@@ -164,8 +164,8 @@ impl PluginInfo {
     ) -> Result<Self, PluginError> {
         let addon_path = project_path.join(PLUGIN_CFG);
         if let Ok(cfg_contents) = io_adapter.read_file_to_string(&addon_path) {
-            let addon_cfg =
-                parse_gdsettings_file(&cfg_contents).map_err(ProjectError::MalformedProject)?;
+            let addon_cfg = parse_gdsettings_file(&cfg_contents)
+                .map_err(|e| ProjectError::MalformedProject(e.with_path(&addon_path)))?;
 
             let name = addon_cfg
                 .get_property("plugin", "name")
@@ -418,15 +418,16 @@ impl<'a, I: IoAdapter> DependencyHandler<'a, I> {
         };
 
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut data = pconf.load(project_path)?;
-        let slug = slugify!(name);
-        data.set_property(DEPS_SECTION, &slug, dependency.to_gdvalue());
+        pconf.update(project_path, |data| {
+            let slug = slugify!(name);
+            data.set_property(DEPS_SECTION, &slug, dependency.to_gdvalue());
 
-        if !no_install {
-            self.install(&dependency, project_path)?;
-        }
+            if !no_install {
+                self.install(&dependency, project_path)?;
+            }
 
-        pconf.save(project_path, data).map_err(Into::into)
+            Ok(())
+        })
     }
 
     /// Remove dependency from project
@@ -434,53 +435,54 @@ impl<'a, I: IoAdapter> DependencyHandler<'a, I> {
         let phandler = ProjectHandler::new(self.io_adapter);
         let project_info = phandler.get_project_info(project_path)?;
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut data = pconf.load(project_path)?;
-        let slug = slugify!(name);
-
-        // Check if dependency is present in project
-        if let Some(value) = data.get_property(DEPS_SECTION, &slug) {
-            let dep = Dependency::from_gdvalue(&slug, &value)?;
-            // Check if dependency is installed
-            if self.is_installed(&dep, project_path) {
-                self.uninstall(&dep, project_path)?;
-                write_stdout!(
-                    self.io_adapter,
-                    "Addon folder {} removed from project {}.\n",
-                    dep.name.color("green"),
-                    project_info.get_versioned_name().color("green")
-                )?;
+        pconf.update(project_path, |data| {
+            let slug = slugify!(name);
+
+            // Check if dependency is present in project
+            if let Some(value) = data.get_property(DEPS_SECTION, &slug) {
+                let dep = Dependency::from_gdvalue(&slug, &value)?;
+                // Check if dependency is installed
+                if self.is_installed(&dep, project_path) {
+                    self.uninstall(&dep, project_path)?;
+                    write_stdout!(
+                        self.io_adapter,
+                        "Addon folder {} removed from project {}.\n",
+                        dep.name.color("green"),
+                        project_info.get_versioned_name().color("green")
+                    )?;
+                }
             }
-        }
 
-        if data.remove_property(DEPS_SECTION, &slug).is_err() {
-            return Err(PluginError::MissingDependency(slug));
-        }
+            if data.remove_property(DEPS_SECTION, &slug).is_err() {
+                return Err(PluginError::MissingDependency(slug));
+            }
 
-        pconf.save(project_path, data).map_err(Into::into)
+            Ok(())
+        })
     }
 
     /// Fork dependency: integrate plugin inside of project
     pub fn fork_dependency(&self, project_path: &Path, name: &str) -> Result<(), PluginError> {
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut data = pconf.load(project_path)?;
-        let slug = slugify!(name);
+        pconf.update(project_path, |data| {
+            let slug = slugify!(name);
+
+            // Check if dependency is present in project
+            if let Some(value) = data.get_property(DEPS_SECTION, &slug) {
+                let mut dep = Dependency::from_gdvalue(&slug, &value)?;
+                // Check if dependency is not installed
+                if !self.is_installed(&dep, project_path) {
+                    // Force installl
+                    self.install(&dep, project_path)?;
+                }
 
-        // Check if dependency is present in project
-        if let Some(value) = data.get_property(DEPS_SECTION, &slug) {
-            let mut dep = Dependency::from_gdvalue(&slug, &value)?;
-            // Check if dependency is not installed
-            if !self.is_installed(&dep, project_path) {
-                // Force installl
-                self.install(&dep, project_path)?;
+                // Set source to current
+                dep.source = DependencySource::Current;
+                data.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
             }
 
-            // Set source to current
-            dep.source = DependencySource::Current;
-            data.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
-            pconf.save(project_path, data)?;
-        }
-
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Sync project dependencies
@@ -493,23 +495,25 @@ impl<'a, I: IoAdapter> DependencyHandler<'a, I> {
         let phandler = ProjectHandler::new(self.io_adapter);
         let pconf = ProjectConfig::new(self.io_adapter);
         let project_info = phandler.get_project_info(project_path)?;
-        let mut conf = pconf.load(project_path)?;
-        let plugins = self.list_plugins_from_project(project_path)?;
-        for plugin in plugins {
-            let slug = slugify!(&plugin.name);
-            // Check if plugin is absent
-            if conf.get_property(DEPS_SECTION, &slug).is_none() {
-                let dep = Dependency::from_plugin_info(&plugin);
-                conf.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
-                write_stdout!(
-                    self.io_adapter,
-                    "Plugin {} added as dependency for project {}.\n",
-                    dep.name.color("green"),
-                    project_info.get_versioned_name().color("green")
-                )?;
+        pconf.update(project_path, |conf| {
+            let plugins = self.list_plugins_from_project(project_path)?;
+            for plugin in plugins {
+                let slug = slugify!(&plugin.name);
+                // Check if plugin is absent
+                if conf.get_property(DEPS_SECTION, &slug).is_none() {
+                    let dep = Dependency::from_plugin_info(&plugin);
+                    conf.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
+                    write_stdout!(
+                        self.io_adapter,
+                        "Plugin {} added as dependency for project {}.\n",
+                        dep.name.color("green"),
+                        project_info.get_versioned_name().color("green")
+                    )?;
+                }
             }
-        }
-        pconf.save(project_path, conf)?;
+
+            Ok::<_, PluginError>(())
+        })?;
 
         // Install dependencies
         let deps = self.list_project_dependencies(project_path)?;
@@ -541,25 +545,27 @@ impl<'a, I: IoAdapter> DependencyHandler<'a, I> {
         let project_info = phandler.get_project_info(project_path)?;
         let plugin_slug = slugify!(plugin_name);
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut conf = pconf.load(project_path)?;
-        let plugins = self.list_plugins_from_project(project_path)?;
-        for plugin in plugins {
-            let slug = slugify!(&plugin.name);
-            if slug == plugin_slug {
-                // Check if plugin is absent
-                if conf.get_property(DEPS_SECTION, &slug).is_none() {
-                    let dep = Dependency::from_plugin_info(&plugin);
-                    conf.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
-                    write_stdout!(
-                        self.io_adapter,
-                        "Plugin {} added as dependency for project {}.\n",
-                        dep.name.color("green"),
-                        project_info.get_versioned_name().color("green")
-                    )?;
+        pconf.update(project_path, |conf| {
+            let plugins = self.list_plugins_from_project(project_path)?;
+            for plugin in plugins {
+                let slug = slugify!(&plugin.name);
+                if slug == plugin_slug {
+                    // Check if plugin is absent
+                    if conf.get_property(DEPS_SECTION, &slug).is_none() {
+                        let dep = Dependency::from_plugin_info(&plugin);
+                        conf.set_property(DEPS_SECTION, &slug, dep.to_gdvalue());
+                        write_stdout!(
+                            self.io_adapter,
+                            "Plugin {} added as dependency for project {}.\n",
+                            dep.name.color("green"),
+                            project_info.get_versioned_name().color("green")
+                        )?;
+                    }
                 }
             }
-        }
-        pconf.save(project_path, conf)?;
+
+            Ok::<_, PluginError>(())
+        })?;
 
         let dep = self.get_dependency(project_path, plugin_name);
         if dep.is_err() {
@@ -1,13 +1,91 @@
 //! Project module.
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use colored::Colorize;
-use gdpm_io::{write_stdout, IoAdapter};
-use gdpm_types::version::GodotVersion;
+use gdpm_io::{write_stdout, Error, IoAdapter};
+use gdpm_types::version::GodotVersionReq;
 use gdsettings_parser::{GdSettings, GdValue};
+use semver::Version;
+use tracing::debug;
 
-use crate::{config::ProjectConfig, error::ProjectError};
+use crate::{
+    config::{
+        ExportPresetsConfig, ProjectConfig, EXPORT_PRESETS_FILENAME, PROJECT_CONFIG_FILENAME,
+    },
+    error::ProjectError,
+};
+
+/// Project version bump
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionBump {
+    /// Bump major version (1.2.3 -> 2.0.0)
+    Major,
+    /// Bump minor version (1.2.3 -> 1.3.0)
+    Minor,
+    /// Bump patch version (1.2.3 -> 1.2.4)
+    Patch,
+    /// Set an explicit version
+    Explicit(Version),
+}
+
+impl VersionBump {
+    /// Apply bump to a version.
+    ///
+    /// Pre-release versions are released first, like `1.3.0-rc.1` -> `1.3.0` for a minor bump.
+    pub fn apply(&self, version: &Version) -> Version {
+        let is_pre = !version.pre.is_empty();
+        let (major, minor, patch) = (version.major, version.minor, version.patch);
+
+        match self {
+            Self::Major if is_pre && minor == 0 && patch == 0 => Version::new(major, 0, 0),
+            Self::Major => Version::new(major + 1, 0, 0),
+            Self::Minor if is_pre && patch == 0 => Version::new(major, minor, 0),
+            Self::Minor => Version::new(major, minor + 1, 0),
+            Self::Patch if is_pre => Version::new(major, minor, patch),
+            Self::Patch => Version::new(major, minor, patch + 1),
+            Self::Explicit(v) => v.clone(),
+        }
+    }
+}
+
+impl FromStr for VersionBump {
+    type Err = ProjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            other => parse_project_version(other).map(Self::Explicit),
+        }
+    }
+}
+
+/// Parse a project version, accepting short forms like `1` or `1.2`.
+pub fn parse_project_version(version: &str) -> Result<Version, ProjectError> {
+    let version = version.trim().trim_start_matches('v');
+    if let Ok(v) = Version::parse(version) {
+        return Ok(v);
+    }
+
+    let parts: Vec<_> = version.split('.').collect();
+    if parts.len() < 3 && parts.iter().all(|p| p.parse::<u64>().is_ok()) {
+        let mut padded = parts.join(".");
+        for _ in parts.len()..3 {
+            padded.push_str(".0");
+        }
+
+        if let Ok(v) = Version::parse(&padded) {
+            return Ok(v);
+        }
+    }
+
+    Err(ProjectError::InvalidVersion(version.into()))
+}
 
 /// Godot project info
 #[derive(Debug)]
@@ -15,7 +93,7 @@ pub struct GdProjectInfo {
     project_name: String,
     version: Option<String>,
     main_scene: Option<String>,
-    engine_version: Option<GodotVersion>,
+    engine_version: Option<GodotVersionReq>,
 }
 
 impl GdProjectInfo {
@@ -34,7 +112,7 @@ impl GdProjectInfo {
         let engine_version = settings
             .get_property("engine", "version")
             .and_then(|x| x.to_str())
-            .map(|x| GodotVersion::try_from(&x[..]));
+            .map(|x| GodotVersionReq::try_from(&x[..]));
 
         let engine_version = match engine_version {
             Some(v) => Some(v?),
@@ -58,8 +136,13 @@ impl GdProjectInfo {
         }
     }
 
-    /// Get engine version
-    pub fn get_engine_version(&self) -> Option<&GodotVersion> {
+    /// Get project version
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get engine version (or alias)
+    pub fn get_engine_version(&self) -> Option<&GodotVersionReq> {
         self.engine_version.as_ref()
     }
 
@@ -71,7 +154,14 @@ impl GdProjectInfo {
         }
 
         if let Some(v) = &self.engine_version {
-            write_stdout!(io, "- Engine version: v{}\n", v.to_string().color("green"))?;
+            // Only prefix exact versions, not requirements or aliases
+            let prefix = if v.exact().is_some() { "v" } else { "" };
+            write_stdout!(
+                io,
+                "- Engine version: {}{}\n",
+                prefix,
+                v.to_string().color("green")
+            )?;
         }
 
         if let Some(s) = &self.main_scene {
@@ -93,6 +183,58 @@ impl<'a, I: IoAdapter> ProjectHandler<'a, I> {
         Self { io_adapter }
     }
 
+    /// Find the nearest folder containing a `project.godot` file, searched upward from a path.
+    pub fn find_project_dir(&self, path: &Path) -> Option<PathBuf> {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+        path.ancestors()
+            .find(|p| {
+                self.io_adapter
+                    .path_is_file(&p.join(PROJECT_CONFIG_FILENAME))
+            })
+            .map(Path::to_path_buf)
+    }
+
+    /// Find Godot projects in a folder, up to a max depth.
+    ///
+    /// Hidden folders and project subfolders are skipped.
+    pub fn find_projects(&self, path: &Path, max_depth: usize) -> Result<Vec<PathBuf>, Error> {
+        if self
+            .io_adapter
+            .path_is_file(&path.join(PROJECT_CONFIG_FILENAME))
+        {
+            return Ok(vec![path.to_owned()]);
+        }
+
+        let mut projects = vec![];
+        if max_depth == 0 {
+            return Ok(projects);
+        }
+
+        let mut entries: Vec<_> = self
+            .io_adapter
+            .read_dir(path)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| !self.io_adapter.path_is_file(p))
+            .collect();
+        entries.sort();
+
+        for entry in entries {
+            let hidden = entry
+                .file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(false);
+            if !hidden {
+                match self.find_projects(&entry, max_depth - 1) {
+                    Ok(p) => projects.extend(p),
+                    Err(e) => debug!("Skipping folder '{}': {}", entry.display(), e),
+                }
+            }
+        }
+
+        Ok(projects)
+    }
+
     /// Get project info.
     ///
     /// Read the project.godot file from a Godot project.
@@ -102,25 +244,482 @@ impl<'a, I: IoAdapter> ProjectHandler<'a, I> {
         pconf.load(path).and_then(GdProjectInfo::from_settings)
     }
 
-    /// Set project engine
+    /// Set project engine, as an exact version or an alias
     pub fn set_project_engine(
         &self,
         path: &Path,
-        version: &GodotVersion,
+        version: &GodotVersionReq,
     ) -> Result<(), ProjectError> {
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut conf = pconf.load(path)?;
-        conf.set_property("engine", "version", GdValue::String(version.to_string()));
-
-        pconf.save(path, conf)
+        pconf.update(path, |conf| {
+            conf.set_property("engine", "version", GdValue::String(version.to_string()));
+            Ok(())
+        })
     }
 
     /// Unset project engine
     pub fn unset_project_engine(&self, path: &Path) -> Result<(), ProjectError> {
         let pconf = ProjectConfig::new(self.io_adapter);
-        let mut conf = pconf.load(path)?;
-        conf.remove_property("engine", "version")?;
+        pconf.update(path, |conf| {
+            conf.remove_property("engine", "version")?;
+            Ok(())
+        })
+    }
+
+    /// Get project setting.
+    ///
+    /// Keys are written `section/property` (e.g. `application/config/version`),
+    /// or `property` for top-level settings.
+    pub fn get_project_setting(&self, path: &Path, key: &str) -> Result<GdValue, ProjectError> {
+        let (section, property) = split_setting_key(key)?;
+        let pconf = ProjectConfig::new(self.io_adapter);
+        let conf = pconf.load(path)?;
+
+        conf.get_property(section, property)
+            .ok_or_else(|| ProjectError::MissingProperty(key.into()))
+    }
+
+    /// Set project setting
+    pub fn set_project_setting(
+        &self,
+        path: &Path,
+        key: &str,
+        value: GdValue,
+    ) -> Result<(), ProjectError> {
+        let (section, property) = split_setting_key(key)?;
+        let pconf = ProjectConfig::new(self.io_adapter);
+        pconf.update(path, |conf| {
+            conf.set_property(section, property, value);
+            Ok(())
+        })
+    }
+
+    /// Unset project setting
+    pub fn unset_project_setting(&self, path: &Path, key: &str) -> Result<(), ProjectError> {
+        let (section, property) = split_setting_key(key)?;
+        let pconf = ProjectConfig::new(self.io_adapter);
+        pconf.update(path, |conf| {
+            conf.remove_property(section, property)
+                .map_err(|_| ProjectError::MissingProperty(key.into()))
+        })
+    }
+
+    /// Bump project version.
+    ///
+    /// A missing version is considered as `0.0.0`. Returns the new version.
+    pub fn bump_project_version(
+        &self,
+        path: &Path,
+        bump: &VersionBump,
+    ) -> Result<Version, ProjectError> {
+        let pconf = ProjectConfig::new(self.io_adapter);
+        pconf.update(path, |conf| {
+            let current = match conf
+                .get_property("application", "config/version")
+                .and_then(|x| x.to_str())
+            {
+                Some(v) => parse_project_version(&v)?,
+                None => Version::new(0, 0, 0),
+            };
+
+            let version = bump.apply(&current);
+            debug!(
+                "Bumping project version from '{}' to '{}' ...",
+                current, version
+            );
+            conf.set_property(
+                "application",
+                "config/version",
+                GdValue::String(version.to_string()),
+            );
+
+            Ok(version)
+        })
+    }
+
+    /// Update version fields of every export preset.
+    ///
+    /// Only fields already present are updated: `application/version` and
+    /// `version/name` are set to the version, `version/code` is incremented.
+    /// Returns the number of updated presets.
+    pub fn bump_export_presets_version(
+        &self,
+        path: &Path,
+        version: &Version,
+    ) -> Result<usize, ProjectError> {
+        let econf = ExportPresetsConfig::new(self.io_adapter);
+        econf.update(path, |conf| {
+            let version_name = GdValue::String(version.to_string());
+            let mut updated = 0;
+
+            let option_sections: Vec<_> = conf
+                .get_map()
+                .iter()
+                .filter(|(name, _)| name.starts_with("preset.") && name.ends_with(".options"))
+                .map(|(name, section)| (name.clone(), section.clone()))
+                .collect();
+
+            for (name, section) in option_sections {
+                let mut changed = false;
+                for property in ["application/version", "version/name"] {
+                    if section.contains_key(property) {
+                        conf.set_property(&name, property, version_name.clone());
+                        changed = true;
+                    }
+                }
+
+                if let Some(code) = section.get("version/code").and_then(|x| x.to_i64()) {
+                    conf.set_property(&name, "version/code", GdValue::Int(code + 1));
+                    changed = true;
+                }
+
+                if changed {
+                    updated += 1;
+                }
+            }
+
+            Ok(updated)
+        })
+    }
+
+    /// Commit the project version files and create a git tag.
+    pub fn tag_project_version(
+        &self,
+        path: &Path,
+        version: &Version,
+        tag_prefix: &str,
+    ) -> Result<String, ProjectError> {
+        let tag = format!("{}{}", tag_prefix, version);
+        let mut files = vec![PROJECT_CONFIG_FILENAME];
+        if ExportPresetsConfig::new(self.io_adapter).exists(path) {
+            files.push(EXPORT_PRESETS_FILENAME);
+        }
+
+        let message = format!("Bump version to {}", version);
+        self.run_git(path, &[&["add", "--"][..], &files].concat())?;
+        self.run_git(
+            path,
+            &[&["commit", "-m", &message, "--"][..], &files].concat(),
+        )?;
+        self.run_git(path, &["tag", "-a", &tag, "-m", &message])?;
+
+        Ok(tag)
+    }
+
+    /// Run a git command in a project folder.
+    fn run_git(&self, path: &Path, args: &[&str]) -> Result<(), ProjectError> {
+        let args: Vec<_> = args.iter().map(|a| a.to_string()).collect();
+        Ok(self.io_adapter.run_command("git", &args, path)?)
+    }
+}
+
+/// Split a `section/property` setting key.
+fn split_setting_key(key: &str) -> Result<(&str, &str), ProjectError> {
+    let (section, property) = key.split_once('/').unwrap_or(("", key));
+    if property.is_empty() || property.starts_with('/') {
+        return Err(ProjectError::InvalidSettingKey(key.into()));
+    }
+
+    Ok((section, property))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use gdpm_io::{Error, FileLock, MockIoAdapter};
+    use gdsettings_parser::GdValue;
+    use mockall::{predicate, Sequence};
+    use semver::Version;
+
+    use crate::{
+        error::ProjectError,
+        project::{parse_project_version, ProjectHandler, VersionBump},
+    };
+
+    const PROJECT: &str = indoc::indoc! {r#"
+        config_version=5
+
+        [application]
+        config/name="Project"
+        config/version="1.0.0"
+    "#};
+
+    fn project_adapter() -> MockIoAdapter {
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/project.godot")))
+            .returning(|_| true);
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/project.godot")))
+            .returning(|_| Ok(PROJECT.into()));
+        lock_adapter(&mut adapter);
+        adapter
+    }
+
+    fn lock_adapter(adapter: &mut MockIoAdapter) {
+        adapter
+            .expect_get_user_configuration_directory()
+            .returning(|| Ok(PathBuf::from("/config")));
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/config/gdpm")))
+            .returning(|_| true);
+        adapter.expect_canonicalize().returning(|p| p.to_owned());
+        adapter
+            .expect_lock_file()
+            .withf(|p| p.starts_with("/config/gdpm/locks"))
+            .returning(|_| Ok(FileLock::default()));
+    }
+
+    #[test]
+    fn test_get_project_setting() {
+        let adapter = project_adapter();
+        let phandler = ProjectHandler::new(&adapter);
+
+        assert_eq!(
+            phandler
+                .get_project_setting(Path::new("/"), "application/config/version")
+                .unwrap(),
+            GdValue::String("1.0.0".into())
+        );
+        assert_eq!(
+            phandler
+                .get_project_setting(Path::new("/"), "config_version")
+                .unwrap(),
+            GdValue::Int(5)
+        );
+        assert!(matches!(
+            phandler.get_project_setting(Path::new("/"), "application/config/missing"),
+            Err(ProjectError::MissingProperty(_))
+        ));
+        assert!(matches!(
+            phandler.get_project_setting(Path::new("/"), "application/"),
+            Err(ProjectError::InvalidSettingKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_project_setting() {
+        let mut adapter = project_adapter();
+        adapter
+            .expect_write_string_to_file()
+            .withf(|path, contents| {
+                path == Path::new("/project.godot")
+                    && contents.contains("[display]\nwindow/size/viewport_width = 1280\n")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let phandler = ProjectHandler::new(&adapter);
+        phandler
+            .set_project_setting(
+                Path::new("/"),
+                "display/window/size/viewport_width",
+                GdValue::Int(1280),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_unset_project_setting() {
+        let mut adapter = project_adapter();
+        adapter
+            .expect_write_string_to_file()
+            .withf(|path, contents| {
+                path == Path::new("/project.godot") && !contents.contains("config/version")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let phandler = ProjectHandler::new(&adapter);
+        phandler
+            .unset_project_setting(Path::new("/"), "application/config/version")
+            .unwrap();
+        assert!(matches!(
+            phandler.unset_project_setting(Path::new("/"), "application/config/missing"),
+            Err(ProjectError::MissingProperty(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_project_version() {
+        assert_eq!(parse_project_version("1").unwrap(), Version::new(1, 0, 0));
+        assert_eq!(parse_project_version("1.2").unwrap(), Version::new(1, 2, 0));
+        assert_eq!(
+            parse_project_version("v1.2.3").unwrap(),
+            Version::new(1, 2, 3)
+        );
+        assert_eq!(
+            parse_project_version("1.2.3-rc.1").unwrap().to_string(),
+            "1.2.3-rc.1"
+        );
+        assert!(parse_project_version("1.2.beta").is_err());
+    }
+
+    #[test]
+    fn test_version_bump() {
+        let check = |bump: &str, version: &str, expected: &str| {
+            let bump: VersionBump = bump.parse().unwrap();
+            let version = parse_project_version(version).unwrap();
+            assert_eq!(bump.apply(&version).to_string(), expected);
+        };
+
+        check("major", "1.2.3", "2.0.0");
+        check("minor", "1.2.3", "1.3.0");
+        check("patch", "1.2.3", "1.2.4");
+        check("patch", "1.2.3-rc.1", "1.2.3");
+        check("minor", "1.3.0-rc.1", "1.3.0");
+        check("minor", "1.3.1-rc.1", "1.4.0");
+        check("major", "2.0.0-beta", "2.0.0");
+        check("4.0.0-alpha.1", "1.2.3", "4.0.0-alpha.1");
+    }
+
+    #[test]
+    fn test_bump_project_version() {
+        let mut adapter = project_adapter();
+        adapter
+            .expect_write_string_to_file()
+            .withf(|path, contents| {
+                path == Path::new("/project.godot")
+                    && contents.contains("config/version = \"1.1.0\"")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let phandler = ProjectHandler::new(&adapter);
+        assert_eq!(
+            phandler
+                .bump_project_version(Path::new("/"), &VersionBump::Minor)
+                .unwrap(),
+            Version::new(1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_bump_export_presets_version() {
+        const PRESETS: &str = indoc::indoc! {r#"
+            [preset.0]
+            name="Android"
+            platform="Android"
+
+            [preset.0.options]
+            version/code=4
+            version/name="1.0.0"
+
+            [preset.1]
+            name="Linux"
+            platform="Linux/X11"
+
+            [preset.1.options]
+            binary_format/embed_pck=false
+        "#};
+
+        let mut adapter = MockIoAdapter::new();
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/export_presets.cfg")))
+            .returning(|_| true);
+        lock_adapter(&mut adapter);
+        adapter
+            .expect_read_file_to_string()
+            .with(predicate::eq(Path::new("/export_presets.cfg")))
+            .returning(|_| Ok(PRESETS.into()));
+        adapter
+            .expect_write_string_to_file()
+            .withf(|path, contents| {
+                path == Path::new("/export_presets.cfg")
+                    && contents.contains("version/code = 5\nversion/name = \"1.1.0\"")
+                    && !contents.contains("[preset.1.options]\napplication/version")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let phandler = ProjectHandler::new(&adapter);
+        assert_eq!(
+            phandler
+                .bump_export_presets_version(Path::new("/"), &Version::new(1, 1, 0))
+                .unwrap(),
+            1
+        );
+    }
+
+    fn git_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tag_project_version() {
+        let mut adapter = MockIoAdapter::new();
+        let mut seq = Sequence::new();
+        adapter
+            .expect_path_exists()
+            .with(predicate::eq(Path::new("/game/export_presets.cfg")))
+            .returning(|_| true);
+        for args in [
+            git_args(&["add", "--", "project.godot", "export_presets.cfg"]),
+            git_args(&[
+                "commit",
+                "-m",
+                "Bump version to 1.1.0",
+                "--",
+                "project.godot",
+                "export_presets.cfg",
+            ]),
+            git_args(&["tag", "-a", "v1.1.0", "-m", "Bump version to 1.1.0"]),
+        ] {
+            adapter
+                .expect_run_command()
+                .with(
+                    predicate::eq("git"),
+                    predicate::eq(args),
+                    predicate::eq(Path::new("/game")),
+                )
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_, _, _| Ok(()));
+        }
+
+        let phandler = ProjectHandler::new(&adapter);
+        assert_eq!(
+            phandler
+                .tag_project_version(Path::new("/game"), &Version::new(1, 1, 0), "v")
+                .unwrap(),
+            "v1.1.0"
+        );
+    }
+
+    #[test]
+    fn test_tag_project_version_failed() {
+        let mut adapter = MockIoAdapter::new();
+        adapter.expect_path_exists().returning(|_| false);
+        adapter
+            .expect_run_command()
+            .with(
+                predicate::eq("git"),
+                predicate::eq(git_args(&["add", "--", "project.godot"])),
+                predicate::eq(Path::new("/game")),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        // Nothing is tagged when the commit fails
+        adapter
+            .expect_run_command()
+            .withf(|_, args, _| args[0] == "commit")
+            .times(1)
+            .returning(|p, a, _| {
+                Err(Error::CommandFailed(
+                    format!("{} {}", p, a.join(" ")),
+                    "exit status: 1".into(),
+                ))
+            });
 
-        pconf.save(path, conf)
+        let phandler = ProjectHandler::new(&adapter);
+        assert!(matches!(
+            phandler.tag_project_version(Path::new("/game"), &Version::new(1, 1, 0), "v"),
+            Err(ProjectError::IoError(Error::CommandFailed(..)))
+        ));
     }
 }
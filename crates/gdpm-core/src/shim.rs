@@ -0,0 +1,114 @@
+//! Shim module.
+
+use std::path::{Path, PathBuf};
+
+use gdpm_io::{Error, IoAdapter};
+
+use crate::config::UserDir;
+
+/// Default shims directory, in the user configuration directory.
+pub const SHIMS_DIR: &str = "shims";
+
+#[cfg(windows)]
+const SHIM_FILENAME: &str = "godot.cmd";
+#[cfg(not(windows))]
+const SHIM_FILENAME: &str = "godot";
+
+/// Shim handler.
+pub struct ShimHandler<'a, I: IoAdapter> {
+    io_adapter: &'a I,
+}
+
+impl<'a, I: IoAdapter> ShimHandler<'a, I> {
+    /// Creates a new shim handler.
+    pub fn new(io_adapter: &'a I) -> Self {
+        Self { io_adapter }
+    }
+
+    /// Get or create default shims directory.
+    pub fn get_or_create_default_directory(&self) -> Result<PathBuf, Error> {
+        UserDir::new(self.io_adapter).get_or_create_directory(Path::new(SHIMS_DIR))
+    }
+
+    /// Get shim path in a directory.
+    pub fn get_shim_path(&self, directory: &Path) -> PathBuf {
+        directory.join(SHIM_FILENAME)
+    }
+
+    /// Install `godot` shim in a directory, calling `gdpm_path`.
+    pub fn install(&self, directory: &Path, gdpm_path: &Path) -> Result<PathBuf, Error> {
+        if !self.io_adapter.path_exists(directory) {
+            self.io_adapter.create_dir(directory)?;
+        }
+
+        let path = self.get_shim_path(directory);
+        self.io_adapter
+            .write_string_to_file(&path, &Self::render(gdpm_path))?;
+        self.io_adapter.set_executable(&path)?;
+
+        Ok(path)
+    }
+
+    /// Remove `godot` shim from a directory, if it exists.
+    pub fn uninstall(&self, directory: &Path) -> Result<Option<PathBuf>, Error> {
+        let path = self.get_shim_path(directory);
+        if self.io_adapter.path_is_file(&path) {
+            self.io_adapter.remove_file(&path)?;
+            Ok(Some(path))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[cfg(windows)]
+    fn render(gdpm_path: &Path) -> String {
+        format!(
+            "@echo off\r\n\"{}\" shim exec -- %*\r\n",
+            gdpm_path.display()
+        )
+    }
+
+    #[cfg(not(windows))]
+    fn render(gdpm_path: &Path) -> String {
+        format!(
+            "#!/bin/sh\nexec '{}' shim exec -- \"$@\"\n",
+            gdpm_path.display().to_string().replace('\'', r"'\''")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use gdpm_io::MockIoAdapter;
+    use mockall::predicate;
+
+    use super::ShimHandler;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_install() {
+        let mut adapter = MockIoAdapter::new();
+        adapter.expect_path_exists().returning(|_| true);
+        adapter
+            .expect_write_string_to_file()
+            .with(
+                predicate::eq(Path::new("/home/user/bin/godot")),
+                predicate::eq("#!/bin/sh\nexec '/opt/gdpm'\\''s/gdpm' shim exec -- \"$@\"\n"),
+            )
+            .times(1)
+            .returning(|_, _| Ok(()));
+        adapter
+            .expect_set_executable()
+            .with(predicate::eq(Path::new("/home/user/bin/godot")))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let shandler = ShimHandler::new(&adapter);
+        let path = shandler
+            .install(Path::new("/home/user/bin"), Path::new("/opt/gdpm's/gdpm"))
+            .unwrap();
+        assert_eq!(path, Path::new("/home/user/bin/godot"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Filesystem integration tests.
+//!
+//! These tests run on a temporary folder, used as user configuration directory.
+//! File system operations are delegated to [`DefaultIoAdapter`], other operations
+//! (archives, commands, locks) are mocked by each test.
+
+use std::path::Path;
+
+use gdpm_core::{
+    build::{get_build_kind, BuildHandler, BuildOptions},
+    engine::{EngineHandler, ExportTemplatesIssue},
+};
+use gdpm_io::{DefaultIoAdapter, Error, FileLock, IoAdapter, MockIoAdapter};
+use gdpm_types::version::GodotVersion;
+use mockall::predicate;
+
+fn fs_adapter(root: &Path) -> MockIoAdapter {
+    let mut adapter = MockIoAdapter::new();
+    let config_dir = root.to_owned();
+    adapter
+        .expect_get_user_configuration_directory()
+        .returning(move || Ok(config_dir.clone()));
+    adapter
+        .expect_path_exists()
+        .returning(|p| DefaultIoAdapter.path_exists(p));
+    adapter
+        .expect_path_is_file()
+        .returning(|p| DefaultIoAdapter.path_is_file(p));
+    adapter
+        .expect_canonicalize()
+        .returning(|p| DefaultIoAdapter.canonicalize(p));
+    adapter
+        .expect_create_dir()
+        .returning(|p| DefaultIoAdapter.create_dir(p));
+    adapter
+        .expect_create_file()
+        .returning(|p| DefaultIoAdapter.create_file(p));
+    adapter
+        .expect_read_dir()
+        .returning(|p| DefaultIoAdapter.read_dir(p));
+    adapter
+        .expect_read_file_to_string()
+        .returning(|p| DefaultIoAdapter.read_file_to_string(p));
+    adapter
+        .expect_write_string_to_file()
+        .returning(|p, c| DefaultIoAdapter.write_string_to_file(p, c));
+    adapter
+        .expect_copy_file()
+        .returning(|s, d| DefaultIoAdapter.copy_file(s, d));
+    adapter
+        .expect_rename()
+        .returning(|s, d| DefaultIoAdapter.rename(s, d));
+    adapter
+        .expect_remove_file()
+        .returning(|p| DefaultIoAdapter.remove_file(p));
+    adapter
+        .expect_remove_dir_all()
+        .returning(|p| DefaultIoAdapter.remove_dir_all(p));
+    adapter
+}
+
+#[test]
+fn test_verify_export_templates() {
+    let root = tempfile::tempdir().unwrap();
+    let templates = root.path().join("Godot").join("export_templates");
+    std::fs::create_dir_all(templates.join("4.3.stable")).unwrap();
+    std::fs::create_dir_all(templates.join("4.2.stable.mono")).unwrap();
+    std::fs::write(templates.join("4.3.stable/version.txt"), "4.3.stable\n").unwrap();
+    std::fs::write(templates.join("4.3.stable/linux_release.x86_64"), "").unwrap();
+    std::fs::write(
+        templates.join("4.2.stable.mono/version.txt"),
+        "4.2.rc1.mono",
+    )
+    .unwrap();
+
+    let adapter = fs_adapter(root.path());
+    let ehandler = EngineHandler::new(&adapter);
+    let version = |s| GodotVersion::try_from(s).unwrap();
+
+    let installed: Vec<_> = ehandler
+        .list_export_templates()
+        .unwrap()
+        .into_iter()
+        .map(|(v, _)| v.to_string())
+        .collect();
+    assert_eq!(installed, vec!["4.2.mono", "4.3"]);
+
+    assert_eq!(
+        ehandler.verify_export_templates(&version("4.3")).unwrap(),
+        vec![]
+    );
+    assert_eq!(
+        ehandler
+            .verify_export_templates(&version("4.2.mono"))
+            .unwrap(),
+        vec![
+            ExportTemplatesIssue::VersionMismatch("4.2.stable.mono".into(), "4.2.rc1.mono".into()),
+            ExportTemplatesIssue::NoTemplates
+        ]
+    );
+
+    // Removing the only template is detected
+    std::fs::remove_file(templates.join("4.3.stable/linux_release.x86_64")).unwrap();
+    assert_eq!(
+        ehandler.verify_export_templates(&version("4.3")).unwrap(),
+        vec![ExportTemplatesIssue::NoTemplates]
+    );
+}
+
+#[test]
+fn test_build() {
+    let root = tempfile::tempdir().unwrap();
+
+    let mut adapter = fs_adapter(root.path());
+    adapter.expect_set_executable().returning(|_| Ok(()));
+    adapter
+        .expect_run_command_with_output()
+        .with(
+            predicate::function(|p: &Path| p.ends_with("4.3.custom.mybranch/godot")),
+            predicate::eq(vec!["--version".to_string()]),
+            predicate::always(),
+        )
+        .returning(|_, _, _| Ok("4.3.stable.custom_build.abcdef\n".into()));
+    adapter
+        .expect_lock_file()
+        .returning(|_| Ok(FileLock::default()));
+
+    // Stubbed git and scons
+    let create_file = |dir: &Path, name: &str, contents: &str| {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| Error::CreateFolderError(dir.into(), e.to_string()))?;
+        DefaultIoAdapter.write_string_to_file(&dir.join(name), contents)
+    };
+    adapter
+        .expect_run_command()
+        .with(
+            predicate::eq("git"),
+            predicate::function(|args: &[String]| args[0] == "clone"),
+            predicate::always(),
+        )
+        .times(1)
+        .returning(move |_, args, _| {
+            create_file(
+                Path::new(&args[2]),
+                "version.py",
+                "major = 4\nminor = 3\npatch = 0\n",
+            )
+        });
+    adapter
+        .expect_run_command()
+        .with(
+            predicate::eq("git"),
+            predicate::always(),
+            predicate::function(|dir: &Path| dir.join("version.py").is_file()),
+        )
+        .returning(|_, _, _| Ok(()));
+    adapter
+        .expect_run_command()
+        .with(
+            predicate::eq("scons"),
+            predicate::always(),
+            predicate::always(),
+        )
+        .times(3)
+        .returning(move |_, args, current_dir| {
+            let target = args[0].trim_start_matches("target=");
+            let name = format!("godot.linuxbsd.{}.x86_64", target);
+            create_file(&current_dir.join("bin"), &name, "")
+        });
+
+    let bhandler = BuildHandler::new(&adapter);
+    let source_dir = bhandler
+        .checkout("https://example.com/godot.git", "my-branch")
+        .unwrap();
+    let kind = get_build_kind("custom.mybranch").unwrap();
+    let version = bhandler.get_build_version(&source_dir, kind).unwrap();
+    let output = bhandler
+        .build(
+            &source_dir,
+            &version,
+            &BuildOptions {
+                scons_args: vec![],
+                templates: true,
+            },
+        )
+        .unwrap();
+    assert!(output.engine.path.is_file());
+
+    let templates_path = output.templates_path.unwrap();
+    assert!(templates_path.join("linux_release.x86_64").is_file());
+    assert!(templates_path.join("linux_debug.x86_64").is_file());
+    assert_eq!(
+        std::fs::read_to_string(templates_path.join("version.txt")).unwrap(),
+        "4.3.custom.mybranch"
+    );
+
+    let config = std::fs::read_to_string(root.path().join("gdpm/gdpm.cfg")).unwrap();
+    assert!(config.contains("4.3.custom.mybranch"));
+    assert!(config.contains("abcdef"));
+}
@@ -0,0 +1,153 @@
+//! Checksums.
+
+use std::{collections::HashMap, io::Read};
+
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::error::DownloadError;
+
+/// Official checksums file name, published with each release.
+pub const OFFICIAL_CHECKSUMS_FILENAME: &str = "SHA512-SUMS.txt";
+
+/// Expected file checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    /// SHA-256 hex digest
+    Sha256(String),
+    /// SHA-512 hex digest
+    Sha512(String),
+}
+
+impl Checksum {
+    /// Get expected hex digest.
+    pub fn expected(&self) -> &str {
+        match self {
+            Self::Sha256(h) | Self::Sha512(h) => h,
+        }
+    }
+
+    /// Compute hex digest of data, using the same algorithm.
+    pub fn compute<R: Read>(&self, mut data: R) -> std::io::Result<String> {
+        match self {
+            Self::Sha256(_) => {
+                let mut hasher = Sha256::new();
+                std::io::copy(&mut data, &mut hasher)?;
+                Ok(to_hex(&hasher.finalize()))
+            }
+            Self::Sha512(_) => {
+                let mut hasher = Sha512::new();
+                std::io::copy(&mut data, &mut hasher)?;
+                Ok(to_hex(&hasher.finalize()))
+            }
+        }
+    }
+
+    /// Verify data against the checksum.
+    pub fn verify<R: Read>(&self, name: &str, data: R) -> Result<(), DownloadError> {
+        let actual = self.compute(data).map_err(DownloadError::IoError)?;
+        if !actual.eq_ignore_ascii_case(self.expected()) {
+            return Err(DownloadError::ChecksumMismatch {
+                name: name.into(),
+                expected: self.expected().to_lowercase(),
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Checksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sha256(h) => write!(f, "sha256:{}", h),
+            Self::Sha512(h) => write!(f, "sha512:{}", h),
+        }
+    }
+}
+
+/// SHA-512 checksums of release files, indexed by file name.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Checksums(HashMap<String, String>);
+
+impl Checksums {
+    /// Parse a `sha512sum`-like file (`<digest>  <file name>` per line).
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+                // Binary mode entries are prefixed with '*'
+                let name = name.trim_start().trim_start_matches('*');
+                Some((name.to_string(), digest.to_lowercase()))
+            })
+            .collect();
+
+        Self(entries)
+    }
+
+    /// Get checksum for file name.
+    pub fn get(&self, name: &str) -> Option<Checksum> {
+        self.0.get(name).cloned().map(Checksum::Sha512)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes.iter().fold(String::new(), |mut acc, b| {
+        let _ = write!(acc, "{:02x}", b);
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let checksum = Checksum::Sha256(
+            "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824".into(),
+        );
+        assert!(checksum.verify("hello.txt", &b"hello"[..]).is_ok());
+
+        match checksum.verify("hello.txt", &b"hello!"[..]) {
+            Err(DownloadError::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(name, "hello.txt");
+                assert_eq!(
+                    expected,
+                    "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                );
+                assert_eq!(
+                    actual,
+                    "ce06092fb948d9ffac7d1a376e404b26b7575bcc11ee05a4615fef4fec3a308b"
+                );
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let checksums = Checksums::parse(indoc::indoc! {"
+            9b75c1a5ab5c8ac5d6bbcaa2a7e5e8b9  Godot_v4.3-stable_linux.x86_64.zip
+            1D2A7E1A2E7C0B6B3C5E9C3B0A1F7E21 *Godot_v4.3-stable_export_templates.tpz
+
+        "});
+
+        assert_eq!(
+            checksums.get("Godot_v4.3-stable_linux.x86_64.zip"),
+            Some(Checksum::Sha512("9b75c1a5ab5c8ac5d6bbcaa2a7e5e8b9".into()))
+        );
+        assert_eq!(
+            checksums.get("Godot_v4.3-stable_export_templates.tpz"),
+            Some(Checksum::Sha512("1d2a7e1a2e7c0b6b3c5e9c3b0a1f7e21".into()))
+        );
+        assert_eq!(checksums.get("missing.zip"), None);
+    }
+}
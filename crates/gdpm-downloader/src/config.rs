@@ -0,0 +1,183 @@
+//! Download configuration.
+
+use std::{path::PathBuf, time::Duration};
+
+use gdsettings_parser::{GdSettings, GdValue};
+
+use crate::error::DownloadError;
+
+/// Download section name, in the global configuration.
+pub const DOWNLOAD_SECTION: &str = "download";
+
+/// Download configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadConfig {
+    /// Retries after a failed request
+    pub retries: u32,
+    /// Delay before the first retry, doubled on each retry
+    pub retry_delay: Duration,
+    /// Connection timeout
+    pub connect_timeout: Duration,
+    /// Read timeout
+    pub read_timeout: Duration,
+    /// Proxy URL, overriding the `HTTPS_PROXY` / `HTTP_PROXY` environment variables
+    pub proxy: Option<String>,
+    /// Additional PEM CA certificate
+    pub ca_certificate: Option<PathBuf>,
+    /// GitHub token, used for API requests
+    pub github_token: Option<String>,
+    /// Time-to-live of the remote versions cache
+    pub remote_cache_ttl: Duration,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            proxy: None,
+            ca_certificate: None,
+            github_token: None,
+            remote_cache_ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Read configuration from the `[download]` section of settings.
+    ///
+    /// Missing keys use default values, and the GitHub token falls back
+    /// to the `GITHUB_TOKEN` environment variable.
+    pub fn from_settings(settings: &GdSettings) -> Result<Self, DownloadError> {
+        let mut config = Self::default();
+        let get = |key: &str| settings.get_property(DOWNLOAD_SECTION, key);
+
+        if let Some(v) = get("retries") {
+            config.retries = v
+                .to_i32()
+                .and_then(|i| u32::try_from(i).ok())
+                .ok_or_else(|| invalid("retries", "a positive integer"))?;
+        }
+        if let Some(v) = get("retry_delay") {
+            config.retry_delay =
+                to_duration(&v).ok_or_else(|| invalid("retry_delay", "seconds"))?;
+        }
+        if let Some(v) = get("connect_timeout") {
+            config.connect_timeout =
+                to_duration(&v).ok_or_else(|| invalid("connect_timeout", "seconds"))?;
+        }
+        if let Some(v) = get("read_timeout") {
+            config.read_timeout =
+                to_duration(&v).ok_or_else(|| invalid("read_timeout", "seconds"))?;
+        }
+        if let Some(v) = get("proxy") {
+            config.proxy = Some(v.to_str().ok_or_else(|| invalid("proxy", "a string"))?);
+        }
+        if let Some(v) = get("ca_certificate") {
+            config.ca_certificate = Some(
+                v.to_str()
+                    .ok_or_else(|| invalid("ca_certificate", "a string"))?
+                    .into(),
+            );
+        }
+
+        if let Some(v) = get("remote_cache_ttl") {
+            config.remote_cache_ttl =
+                to_duration(&v).ok_or_else(|| invalid("remote_cache_ttl", "seconds"))?;
+        }
+
+        config.github_token = match get("github_token") {
+            Some(v) => Some(
+                v.to_str()
+                    .ok_or_else(|| invalid("github_token", "a string"))?,
+            ),
+            None => std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+        };
+
+        Ok(config)
+    }
+
+    /// Get delay before a retry (starting at 0).
+    pub fn get_retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+fn to_duration(value: &GdValue) -> Option<Duration> {
+    let secs = match value {
+        GdValue::Int(i) => *i as f64,
+        GdValue::Float(f) => *f,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+fn invalid(key: &str, expected: &str) -> DownloadError {
+    DownloadError::InvalidConfig(key.into(), expected.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use gdsettings_parser::parse_gdsettings_file;
+
+    use super::DownloadConfig;
+    use crate::error::DownloadError;
+
+    #[test]
+    fn test_from_settings() {
+        let settings = parse_gdsettings_file(indoc::indoc! {r#"
+            [download]
+
+            retries=5
+            retry_delay=0.5
+            read_timeout=60
+            proxy="http://localhost:3128"
+            github_token="abcd"
+            remote_cache_ttl=3600
+        "#})
+        .unwrap();
+
+        let config = DownloadConfig::from_settings(&settings).unwrap();
+        assert_eq!(
+            config,
+            DownloadConfig {
+                retries: 5,
+                retry_delay: Duration::from_millis(500),
+                read_timeout: Duration::from_secs(60),
+                proxy: Some("http://localhost:3128".into()),
+                github_token: Some("abcd".into()),
+                remote_cache_ttl: Duration::from_secs(3600),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_settings_invalid() {
+        let settings = parse_gdsettings_file(indoc::indoc! {r#"
+            [download]
+
+            retries=-1
+        "#})
+        .unwrap();
+
+        assert!(matches!(
+            DownloadConfig::from_settings(&settings),
+            Err(DownloadError::InvalidConfig(k, _)) if k == "retries"
+        ));
+    }
+
+    #[test]
+    fn test_get_retry_delay() {
+        let config = DownloadConfig::default();
+        assert_eq!(config.get_retry_delay(0), Duration::from_secs(1));
+        assert_eq!(config.get_retry_delay(1), Duration::from_secs(2));
+        assert_eq!(config.get_retry_delay(3), Duration::from_secs(8));
+    }
+}
@@ -1,41 +1,77 @@
 //! Download module.
 
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
 use reqwest::Url;
 use tracing::info;
 
-use crate::{error::DownloadError, DownloadAdapter};
-use gdpm_types::version::{GodotVersion, SystemVersion};
+use crate::{
+    checksum::{Checksum, Checksums, OFFICIAL_CHECKSUMS_FILENAME},
+    error::DownloadError,
+    DownloadAdapter,
+};
+use gdpm_types::version::{self, GodotVersion, SystemVersion};
+
+/// Official mirror URL, used as a last resort.
+pub const OFFICIAL_MIRROR_URL: &str =
+    "https://github.com/godotengine/godot-builds/releases/download/";
 
 /// Downloader.
 pub struct Downloader;
 
 impl Downloader {
-    /// Get editor URL for version from a mirror URL.
-    pub fn get_official_editor_url_for_version(
-        version: GodotVersion,
-        system: SystemVersion,
-        mirror_url: &str,
-    ) -> String {
-        let kind = version.kind().clone();
-        let mut route = String::new();
-        let path = Url::parse(mirror_url).unwrap();
+    /// Parse a mirror URL, making sure it ends with a slash.
+    pub fn parse_mirror_url(mirror_url: &str) -> Result<String, DownloadError> {
+        let mut url =
+            Url::parse(mirror_url).map_err(|_| DownloadError::InvalidMirror(mirror_url.into()))?;
+        if url.cannot_be_a_base() {
+            return Err(DownloadError::InvalidMirror(mirror_url.into()));
+        }
 
-        // Get version path
-        route.push_str(version.version());
-        route.push('-');
-        route.push_str(&kind.to_string());
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
 
-        // Get name
-        let filename = format!(
+        Ok(url.to_string())
+    }
+
+    /// Get editor archive file name for version.
+    pub fn get_official_editor_filename(
+        version: &GodotVersion,
+        system: SystemVersion,
+    ) -> Result<String, version::Error> {
+        Ok(format!(
             "Godot_v{}-{}_{}.zip",
             version.version(),
-            kind,
-            system.get_archive_basename(version.mono())
-        );
-        route.push('/');
-        route.push_str(&filename);
+            version.kind(),
+            system.get_archive_basename(version)?
+        ))
+    }
 
-        path.join(&route).unwrap().to_string()
+    /// Get export templates file name for version.
+    pub fn get_official_export_templates_filename(version: &GodotVersion) -> String {
+        format!(
+            "Godot_v{}-{}{}_export_templates.tpz",
+            version.version(),
+            version.kind(),
+            if version.mono() { "_mono" } else { "" },
+        )
+    }
+
+    /// Get editor URL for version from a mirror URL.
+    pub fn get_official_editor_url_for_version(
+        version: GodotVersion,
+        system: SystemVersion,
+        mirror_url: &str,
+    ) -> Result<String, version::Error> {
+        let filename = Self::get_official_editor_filename(&version, system)?;
+        Ok(Self::get_official_release_file_url(
+            &version, &filename, mirror_url,
+        ))
     }
 
     /// Get export templates URL for version from a mirror URL.
@@ -43,41 +79,119 @@ impl Downloader {
         version: GodotVersion,
         mirror_url: &str,
     ) -> String {
-        let kind = version.kind().clone();
-        let mut route = String::new();
+        let filename = Self::get_official_export_templates_filename(&version);
+        Self::get_official_release_file_url(&version, &filename, mirror_url)
+    }
+
+    /// Get checksums file URL for version from a mirror URL.
+    pub fn get_official_checksums_url_for_version(
+        version: GodotVersion,
+        mirror_url: &str,
+    ) -> String {
+        Self::get_official_release_file_url(&version, OFFICIAL_CHECKSUMS_FILENAME, mirror_url)
+    }
+
+    fn get_official_release_file_url(
+        version: &GodotVersion,
+        filename: &str,
+        mirror_url: &str,
+    ) -> String {
         let path = Url::parse(mirror_url).unwrap();
 
         // Get version path
-        route.push_str(version.version());
-        route.push('-');
-        route.push_str(&kind.to_string());
-
-        // Get name
-        let filename = format!(
-            "Godot_v{}-{}{}_export_templates.tpz",
-            version.version(),
-            kind,
-            if version.mono() { "_mono" } else { "" },
-        );
-        route.push('/');
-        route.push_str(&filename);
+        let route = format!("{}-{}/{}", version.version(), version.kind(), filename);
 
         path.join(&route).unwrap().to_string()
     }
 
-    /// Download file at URL.
+    /// Download official checksums for version to a target path, from the first mirror which has them.
+    pub async fn download_official_checksums<I: DownloadAdapter>(
+        download_adapter: &I,
+        version: GodotVersion,
+        mirror_urls: &[String],
+        target: &Path,
+    ) -> Result<Checksums, DownloadError> {
+        let urls: Vec<_> = mirror_urls
+            .iter()
+            .map(|m| Self::get_official_checksums_url_for_version(version.clone(), m))
+            .collect();
+        let path = Self::download_file_from_urls(download_adapter, &urls, target, None).await?;
+        let contents = std::fs::read_to_string(&path).map_err(DownloadError::IoError)?;
+        std::fs::remove_file(&path).map_err(DownloadError::IoError)?;
+        Ok(Checksums::parse(&contents))
+    }
+
+    /// Download file to a target path from the first URL which has it, and verify its checksum if any.
+    ///
+    /// URLs are tried in order, falling back to the next one when the file is not found.
+    pub async fn download_file_from_urls<I: DownloadAdapter>(
+        download_adapter: &I,
+        urls: &[String],
+        target: &Path,
+        checksum: Option<&Checksum>,
+    ) -> Result<PathBuf, DownloadError> {
+        let mut not_found = None;
+        for url in urls {
+            let result = match checksum {
+                Some(c) => {
+                    Self::download_verified_file_at_url(download_adapter, url, target, c).await
+                }
+                None => Self::download_file_at_url(download_adapter, url, target, false).await,
+            };
+
+            match result {
+                Err(DownloadError::NotFound(u)) => {
+                    info!(url = u, "File not found, trying next mirror");
+                    not_found = Some(u);
+                }
+                r => return r,
+            }
+        }
+
+        Err(DownloadError::NotFound(not_found.unwrap_or_default()))
+    }
+
+    /// Download file at URL to a target path and verify its checksum.
+    ///
+    /// The downloaded file is removed on mismatch.
+    pub async fn download_verified_file_at_url<I: DownloadAdapter>(
+        download_adapter: &I,
+        url: &str,
+        target: &Path,
+        checksum: &Checksum,
+    ) -> Result<PathBuf, DownloadError> {
+        let path = Self::download_file_at_url(download_adapter, url, target, true).await?;
+        let file = File::open(&path).map_err(DownloadError::IoError)?;
+        if let Err(e) = checksum.verify(url, BufReader::new(file)) {
+            std::fs::remove_file(&path).map_err(DownloadError::IoError)?;
+            return Err(e);
+        }
+
+        info!(url = url, checksum = %checksum, "Checksum verified");
+        Ok(path)
+    }
+
+    /// Download file at URL to a target path.
+    ///
+    /// Partial downloads are only resumed when `resume` is set, otherwise they start over.
     pub async fn download_file_at_url<I: DownloadAdapter>(
         download_adapter: &I,
         url: &str,
-    ) -> Result<Vec<u8>, DownloadError> {
-        info!(url = url, "Will download file at url");
-        download_adapter.download_file_at_url(url).await
+        target: &Path,
+        resume: bool,
+    ) -> Result<PathBuf, DownloadError> {
+        info!(url = url, target = ?target, resume = resume, "Will download file at url");
+        download_adapter
+            .download_file_at_url(url, target, resume)
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::download::Downloader;
+    use crate::{
+        checksum::Checksum, download::Downloader, error::DownloadError, MockDownloadAdapter,
+    };
     use gdpm_types::version::{GodotVersion, GodotVersionKind, SystemVersion};
 
     #[test]
@@ -89,7 +203,8 @@ mod tests {
                     GodotVersion::new(v, k, m),
                     s,
                     root
-                ),
+                )
+                .unwrap(),
                 expected
             );
         };
@@ -133,6 +248,21 @@ mod tests {
             ),
             "http://localhost/subdir/1.2.3-rc2/Godot_v1.2.3-rc2_mono_linux_server_64.zip",
         );
+
+        check(
+            ("4.3", GodotVersionKind::Stable, SystemVersion::X1164, false),
+            "http://localhost/subdir/4.3-stable/Godot_v4.3-stable_linux.x86_64.zip",
+        );
+
+        check(
+            (
+                "4.3",
+                GodotVersionKind::Stable,
+                SystemVersion::LinuxArm64,
+                true,
+            ),
+            "http://localhost/subdir/4.3-stable/Godot_v4.3-stable_mono_linux_arm64.zip",
+        );
     }
 
     #[test]
@@ -168,4 +298,104 @@ mod tests {
             "http://localhost/subdir/1.2.3-rc2/Godot_v1.2.3-rc2_mono_export_templates.tpz",
         );
     }
+
+    #[test]
+    fn test_get_official_checksums_url_for_version() {
+        assert_eq!(
+            Downloader::get_official_checksums_url_for_version(
+                GodotVersion::new("1.2.3", GodotVersionKind::ReleaseCandidate(2), true),
+                "http://localhost/subdir/"
+            ),
+            "http://localhost/subdir/1.2.3-rc2/SHA512-SUMS.txt"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_verified_file_at_url() {
+        let target = std::env::temp_dir().join("gdpm-test-download-verified.zip");
+        let mut adapter = MockDownloadAdapter::new();
+        adapter
+            .expect_download_file_at_url()
+            .withf(|_, _, resume| *resume)
+            .returning(|_, target, _| {
+                std::fs::write(target, b"hello").unwrap();
+                let target = target.to_owned();
+                Box::pin(async { Ok(target) })
+            });
+
+        let valid = Checksum::Sha256(
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".into(),
+        );
+        let path = Downloader::download_verified_file_at_url(
+            &adapter,
+            "http://localhost",
+            &target,
+            &valid,
+        )
+        .await
+        .unwrap();
+        assert_eq!(path, target);
+        assert!(target.exists());
+
+        let invalid = Checksum::Sha256("00".into());
+        assert!(matches!(
+            Downloader::download_verified_file_at_url(
+                &adapter,
+                "http://localhost",
+                &target,
+                &invalid
+            )
+            .await,
+            Err(DownloadError::ChecksumMismatch { .. })
+        ));
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_parse_mirror_url() {
+        assert_eq!(
+            Downloader::parse_mirror_url("https://mirror.local/godot").unwrap(),
+            "https://mirror.local/godot/"
+        );
+        assert_eq!(
+            Downloader::parse_mirror_url("https://mirror.local/godot/").unwrap(),
+            "https://mirror.local/godot/"
+        );
+        assert!(matches!(
+            Downloader::parse_mirror_url("mirror.local"),
+            Err(DownloadError::InvalidMirror(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_file_from_urls() {
+        let target = std::env::temp_dir().join("gdpm-test-download-mirrors.zip");
+        let mut adapter = MockDownloadAdapter::new();
+        adapter
+            .expect_download_file_at_url()
+            .withf(|url, _, resume| url.starts_with("http://first") && !resume)
+            .times(1)
+            .returning(|url, _, _| {
+                let url = url.to_owned();
+                Box::pin(async { Err(DownloadError::NotFound(url)) })
+            });
+        adapter
+            .expect_download_file_at_url()
+            .withf(|url, _, resume| url.starts_with("http://second") && !resume)
+            .times(1)
+            .returning(|_, target, _| {
+                let target = target.to_owned();
+                Box::pin(async { Ok(target) })
+            });
+
+        let urls = vec![
+            "http://first/file.zip".into(),
+            "http://second/file.zip".into(),
+            "http://third/file.zip".into(),
+        ];
+        let path = Downloader::download_file_from_urls(&adapter, &urls, &target, None)
+            .await
+            .unwrap();
+        assert_eq!(path, target);
+    }
 }
@@ -1,5 +1,7 @@
 //! Errors.
 
+use std::path::PathBuf;
+
 use reqwest::StatusCode;
 use thiserror::Error;
 
@@ -15,6 +17,35 @@ pub enum DownloadError {
     ReqwestError(String, #[source] reqwest::Error),
     #[error("Could not create async runtime.")]
     AsyncRuntimeError(#[source] std::io::Error),
+    #[error("Checksum mismatch for '{name}': expected '{expected}', got '{actual}'.")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Invalid download configuration for key '{0}', expected {1}.")]
+    InvalidConfig(String, String),
+    #[error("Invalid mirror URL '{0}'.")]
+    InvalidMirror(String),
+    #[error("Invalid proxy URL '{0}'.")]
+    InvalidProxy(String, #[source] reqwest::Error),
+    #[error("Invalid CA certificate at path '{0}'.")]
+    InvalidCertificate(PathBuf, #[source] reqwest::Error),
+    #[error("Could not create HTTP client.")]
+    ClientError(#[source] reqwest::Error),
     #[error("I/O error.")]
     IoError(#[source] std::io::Error),
 }
+
+impl DownloadError {
+    /// Check if the failed request can be retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ReqwestError(_, e) => !e.is_builder() && !e.is_redirect(),
+            Self::UnexpectedStatusCode(s) => {
+                s.is_server_error() || *s == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,92 +1,328 @@
-use crate::{error::DownloadError, DownloadAdapter};
+use crate::{config::DownloadConfig, error::DownloadError, DownloadAdapter};
 use async_trait::async_trait;
-use tracing::info;
+use gdpm_types::version::GodotVersion;
+use tracing::{info, warn};
 
-use std::io::Write;
+use std::{
+    fs::OpenOptions,
+    future::Future,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use futures_util::StreamExt;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::{Client, Response, StatusCode};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use reqwest::{
+    header::{ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    Certificate, Client, Proxy, Response, StatusCode,
+};
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+static GITHUB_RELEASES_URL: &str = "https://api.github.com/repos/godotengine/godot-builds/releases";
+const GITHUB_RELEASES_PER_PAGE: usize = 100;
 
 /// Default download adapter.
-pub struct DefaultDownloadAdapter;
+///
+/// Progress bars of concurrent downloads are displayed together.
+pub struct DefaultDownloadAdapter {
+    client: Client,
+    config: DownloadConfig,
+    progress: MultiProgress,
+}
 
 impl DefaultDownloadAdapter {
-    async fn download_file_at_url_async(url: &str) -> Result<Vec<u8>, DownloadError> {
-        let client = Client::new();
-        let res = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| DownloadError::ReqwestError(url.into(), e))?;
+    /// Creates a new download adapter from configuration.
+    pub fn new(config: DownloadConfig) -> Result<Self, DownloadError> {
+        let mut builder = Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .connect_timeout(config.connect_timeout)
+            .read_timeout(config.read_timeout);
 
-        match res.status() {
-            StatusCode::OK => Self::download_file_inner(url, res).await,
-            StatusCode::NOT_FOUND => Err(DownloadError::NotFound(url.to_owned())),
-            e => Err(DownloadError::UnexpectedStatusCode(e)),
+        if let Some(proxy) = &config.proxy {
+            let proxy =
+                Proxy::all(proxy).map_err(|e| DownloadError::InvalidProxy(proxy.clone(), e))?;
+            builder = builder.proxy(proxy);
         }
+
+        if let Some(path) = &config.ca_certificate {
+            let pem = std::fs::read(path).map_err(DownloadError::IoError)?;
+            let certificate = Certificate::from_pem(&pem)
+                .map_err(|e| DownloadError::InvalidCertificate(path.clone(), e))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        let client = builder.build().map_err(DownloadError::ClientError)?;
+        Ok(Self {
+            client,
+            config,
+            progress: MultiProgress::new(),
+        })
     }
 
-    async fn download_file_inner(url: &str, res: Response) -> Result<Vec<u8>, DownloadError> {
-        info!(url = url, status = ?res.status(), "File found");
+    async fn with_retries<T, F, Fut>(&self, url: &str, mut request: F) -> Result<T, DownloadError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DownloadError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match request().await {
+                Err(e) if e.is_retryable() && attempt < self.config.retries => {
+                    let delay = self.config.get_retry_delay(attempt);
+                    attempt += 1;
+                    warn!(
+                        url = url,
+                        attempt = attempt,
+                        delay = ?delay,
+                        error = %e,
+                        "Request failed, will retry"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn download_file_at_url_async(
+        &self,
+        url: &str,
+        target: &Path,
+        resume: bool,
+    ) -> Result<PathBuf, DownloadError> {
+        let part_path = Self::get_part_path(target);
+        let validator_path = Self::get_validator_path(target);
+        let mut validator = if resume {
+            Self::read_validator(&validator_path, url)
+        } else {
+            None
+        };
+
+        loop {
+            // Partial files can only be trusted when they come from the same URL and can be revalidated
+            let resume_from = match &validator {
+                Some(_) => std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0),
+                None => {
+                    Self::remove_partial_download(&part_path, &validator_path)?;
+                    0
+                }
+            };
 
-        let total_size = res.content_length().unwrap();
+            let mut request = self.client.get(url);
+            if let (Some(v), true) = (&validator, resume_from > 0) {
+                info!(url = url, offset = resume_from, "Resuming download");
+                request = request
+                    .header(RANGE, format!("bytes={}-", resume_from))
+                    .header(IF_RANGE, v.as_str());
+            }
 
-        let pb = ProgressBar::new(total_size);
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{msg}\n{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})").unwrap()
-            .progress_chars("#>-")
-        );
+            let res = request
+                .send()
+                .await
+                .map_err(|e| DownloadError::ReqwestError(url.into(), e))?;
+
+            match res.status() {
+                // Also returned when the remote file changed since the partial download
+                StatusCode::OK => {
+                    Self::write_validator(&validator_path, url, &res)?;
+                    self.download_file_inner(url, res, &part_path, 0).await?
+                }
+                StatusCode::PARTIAL_CONTENT if resume_from > 0 => {
+                    self.download_file_inner(url, res, &part_path, resume_from)
+                        .await?
+                }
+                StatusCode::RANGE_NOT_SATISFIABLE if resume_from > 0 => {
+                    warn!(url = url, "Partial download is invalid, restarting");
+                    validator = None;
+                    continue;
+                }
+                StatusCode::NOT_FOUND => return Err(DownloadError::NotFound(url.to_owned())),
+                e => return Err(DownloadError::UnexpectedStatusCode(e)),
+            }
+
+            break;
+        }
+
+        std::fs::rename(&part_path, target).map_err(DownloadError::IoError)?;
+        Self::remove_file_if_exists(&validator_path)?;
+        Ok(target.to_owned())
+    }
+
+    async fn download_file_inner(
+        &self,
+        url: &str,
+        res: Response,
+        part_path: &Path,
+        offset: u64,
+    ) -> Result<(), DownloadError> {
+        info!(url = url, status = ?res.status(), "File found");
+
+        let pb = match res.content_length() {
+            Some(length) => {
+                let pb = ProgressBar::new(offset + length);
+                pb.set_style(ProgressStyle::default_bar()
+                    .template("{msg}\n{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})").unwrap()
+                    .progress_chars("#>-")
+                );
+                pb
+            }
+            None => {
+                let pb = ProgressBar::new_spinner();
+                pb.set_style(
+                    ProgressStyle::default_spinner()
+                        .template(
+                            "{msg}\n{spinner:.green} [{elapsed_precise}] {bytes} ({bytes_per_sec})",
+                        )
+                        .unwrap(),
+                );
+                pb
+            }
+        };
+        let pb = self.progress.add(pb);
         pb.set_message(format!("Downloading {}", url));
+        pb.set_position(offset);
 
-        let mut data: Vec<u8> = Vec::with_capacity(total_size as usize);
-        let mut downloaded: u64 = 0;
+        // Append when resuming, start over otherwise
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(part_path)
+            .map_err(DownloadError::IoError)?;
         let mut stream = res.bytes_stream();
 
         while let Some(item) = stream.next().await {
             let chunk = item.map_err(|e| DownloadError::ReqwestError(url.into(), e))?;
-            data.write_all(&chunk).map_err(DownloadError::IoError)?;
-
-            let new = std::cmp::min(downloaded + (chunk.len() as u64), total_size);
-            downloaded = new;
-            pb.set_position(new);
+            file.write_all(&chunk).map_err(DownloadError::IoError)?;
+            pb.inc(chunk.len() as u64);
         }
 
+        file.flush().map_err(DownloadError::IoError)?;
         pb.finish_with_message(format!("Downloaded {}", url));
-        Ok(data)
+        Ok(())
+    }
+
+    fn get_part_path(target: &Path) -> PathBuf {
+        let mut name = target.as_os_str().to_owned();
+        name.push(".part");
+        PathBuf::from(name)
+    }
+
+    fn get_validator_path(target: &Path) -> PathBuf {
+        let mut name = target.as_os_str().to_owned();
+        name.push(".part.validator");
+        PathBuf::from(name)
+    }
+
+    /// Read the validator of a partial download, if it was downloaded from the same URL.
+    fn read_validator(validator_path: &Path, url: &str) -> Option<String> {
+        let contents = std::fs::read_to_string(validator_path).ok()?;
+        let (source, validator) = contents.split_once('\n')?;
+        (source == url && !validator.is_empty()).then(|| validator.to_owned())
+    }
+
+    /// Store the strong validator of a response, so that its partial download can be resumed.
+    ///
+    /// Weak ETags cannot be used with `If-Range`, the Last-Modified date is used instead.
+    fn write_validator(
+        validator_path: &Path,
+        url: &str,
+        res: &Response,
+    ) -> Result<(), DownloadError> {
+        let header = |name| res.headers().get(name).and_then(|v| v.to_str().ok());
+        let validator = header(ETAG)
+            .filter(|etag| !etag.starts_with("W/"))
+            .or_else(|| header(LAST_MODIFIED));
+
+        match validator {
+            Some(v) => std::fs::write(validator_path, format!("{}\n{}", url, v))
+                .map_err(DownloadError::IoError),
+            None => Self::remove_file_if_exists(validator_path),
+        }
+    }
+
+    fn remove_partial_download(
+        part_path: &Path,
+        validator_path: &Path,
+    ) -> Result<(), DownloadError> {
+        Self::remove_file_if_exists(part_path)?;
+        Self::remove_file_if_exists(validator_path)
+    }
+
+    fn remove_file_if_exists(path: &Path) -> Result<(), DownloadError> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(DownloadError::IoError(e)),
+            _ => Ok(()),
+        }
     }
 }
 
 #[async_trait]
 impl DownloadAdapter for DefaultDownloadAdapter {
-    async fn download_file_at_url(&self, url: &str) -> Result<Vec<u8>, DownloadError> {
-        Self::download_file_at_url_async(url).await
+    async fn download_file_at_url(
+        &self,
+        url: &str,
+        target: &Path,
+        resume: bool,
+    ) -> Result<PathBuf, DownloadError> {
+        // Interrupted downloads are resumed from their partial file on retry, when allowed
+        self.with_retries(url, || self.download_file_at_url_async(url, target, resume))
+            .await
     }
 
-    async fn lookup_remote_versions(&self) -> Result<Vec<String>, DownloadError> {
+    async fn lookup_remote_versions(&self) -> Result<Vec<GodotVersion>, DownloadError> {
         #[derive(serde::Deserialize, Debug)]
         struct ApiRelease {
             name: String,
         }
 
-        let response = Client::builder()
-            .user_agent(APP_USER_AGENT)
-            .build()
-            .unwrap()
-            .get("https://api.github.com/repos/godotengine/godot-builds/releases")
-            .send()
-            .await
-            .unwrap()
-            .error_for_status()
-            .unwrap();
+        let mut releases: Vec<ApiRelease> = vec![];
+        for page in 1.. {
+            let url = format!(
+                "{}?per_page={}&page={}",
+                GITHUB_RELEASES_URL, GITHUB_RELEASES_PER_PAGE, page
+            );
+            let url = url.as_str();
+            let page_releases: Vec<ApiRelease> = self
+                .with_retries(url, || async {
+                    let mut request = self.client.get(url);
+                    if let Some(token) = &self.config.github_token {
+                        request = request.bearer_auth(token);
+                    }
+
+                    let response = request
+                        .send()
+                        .await
+                        .map_err(|e| DownloadError::ReqwestError(url.into(), e))?;
 
-        let releases: Vec<ApiRelease> = response.json().await.unwrap();
+                    match response.status() {
+                        s if s.is_success() => response
+                            .json()
+                            .await
+                            .map_err(|e| DownloadError::ReqwestError(url.into(), e)),
+                        StatusCode::NOT_FOUND => Err(DownloadError::NotFound(url.into())),
+                        e => Err(DownloadError::UnexpectedStatusCode(e)),
+                    }
+                })
+                .await?;
+
+            let last_page = page_releases.len() < GITHUB_RELEASES_PER_PAGE;
+            releases.extend(page_releases);
+            if last_page {
+                break;
+            }
+        }
 
         let mut releases: Vec<_> = releases
             .into_iter()
-            .map(|r| r.name.replace("-stable", "").replace('-', "."))
+            .filter_map(|r| {
+                r.name
+                    .replace("-stable", "")
+                    .replace('-', ".")
+                    .parse::<GodotVersion>()
+                    .ok()
+            })
             .collect();
 
         releases.sort();
@@ -1,12 +1,24 @@
 use crate::error::DownloadError;
 use async_trait::async_trait;
+use gdpm_types::version::GodotVersion;
+use std::path::{Path, PathBuf};
 
 /// Download adapter.
 #[async_trait]
 #[mockall::automock]
 pub trait DownloadAdapter {
-    /// Download file at URL.
-    async fn download_file_at_url(&self, url: &str) -> Result<Vec<u8>, DownloadError>;
-    /// Lookup remote versions.
-    async fn lookup_remote_versions(&self) -> Result<Vec<String>, DownloadError>;
+    /// Download file at URL to a target path.
+    ///
+    /// Data is streamed to a `.part` file next to the target, then renamed to the target path once complete.
+    ///
+    /// When `resume` is set, an existing `.part` file from the same URL is resumed with `If-Range`,
+    /// so that it restarts if the remote file changed. Only resume downloads which are verified afterwards.
+    async fn download_file_at_url(
+        &self,
+        url: &str,
+        target: &Path,
+        resume: bool,
+    ) -> Result<PathBuf, DownloadError>;
+    /// Lookup remote versions, sorted from oldest to newest.
+    async fn lookup_remote_versions(&self) -> Result<Vec<GodotVersion>, DownloadError>;
 }
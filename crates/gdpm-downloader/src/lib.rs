@@ -2,6 +2,8 @@
 
 #![warn(missing_docs)]
 
+pub mod checksum;
+pub mod config;
 pub mod download;
 pub mod error;
 mod implementation;
@@ -1,6 +1,6 @@
 //! Errors.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// I/O error.
 #[derive(thiserror::Error, Debug)]
@@ -35,6 +35,18 @@ pub enum Error {
     #[error("Could not copy file '{0}' to '{1}': {2}")]
     CopyFileError(PathBuf, PathBuf, String),
 
+    #[error("Could not rename '{0}' to '{1}': {2}")]
+    RenameError(PathBuf, PathBuf, String),
+
+    #[error("Could not lock file '{0}': {1}")]
+    LockFileError(PathBuf, String),
+
+    #[error("Could not set permissions on file '{0}': {1}")]
+    SetPermissionsError(PathBuf, String),
+
+    #[error("Could not get size of '{0}': {1}")]
+    GetSizeError(PathBuf, String),
+
     #[error("Could not read folder '{0}': {1}")]
     ReadDirError(PathBuf, String),
 
@@ -44,6 +56,12 @@ pub enum Error {
     #[error("Failed to execute command: {0}")]
     CommandExecutionError(String),
 
+    #[error("Command '{0}' failed ({1}).")]
+    CommandFailed(String, String),
+
+    #[error("Command '{0}' timed out after {1:?}.")]
+    CommandTimedOut(String, Duration),
+
     #[error("Cannot get parent path for '{0}'")]
     NoParentFolder(PathBuf),
 
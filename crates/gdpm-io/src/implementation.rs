@@ -1,15 +1,25 @@
-use crate::{error::Error, interface::IoAdapter};
+use crate::{error::Error, interface::IoAdapter, lock::FileLock};
 use colored::Colorize;
+use fs4::FileExt;
 
 use std::{
     fs::{File, OpenOptions, ReadDir},
     io::{Read, Write},
     path::{Path, PathBuf},
+    process::Stdio,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
 };
 use tracing::debug;
 use zip::ZipArchive;
 
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Counter for temporary file names, unique among threads of this process.
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
 /// IO adapter implementation.
+#[derive(Clone)]
 pub struct DefaultIoAdapter;
 
 impl DefaultIoAdapter {
@@ -17,12 +27,32 @@ impl DefaultIoAdapter {
         File::open(path).map_err(|e| Error::OpenFileError(path.to_owned(), e.to_string()))
     }
 
-    fn open_file_write(&self, path: &Path) -> Result<File, Error> {
-        OpenOptions::new()
-            .truncate(true)
-            .write(true)
-            .open(path)
-            .map_err(|e| Error::OpenFileError(path.to_owned(), e.to_string()))
+    /// Get a temporary file path in the same folder as `path`, so that it can be renamed to `path`.
+    fn get_temp_path(path: &Path) -> Result<PathBuf, Error> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| Error::NoParentFolder(path.to_owned()))?;
+        Ok(path.with_file_name(format!(
+            ".{}.{}-{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        )))
+    }
+
+    fn write_temp_file(&self, path: &Path, temp_path: &Path, contents: &[u8]) -> Result<(), Error> {
+        let mut file = File::create(temp_path)
+            .map_err(|e| Error::CreateFileError(temp_path.to_owned(), e.to_string()))?;
+
+        // Keep permissions of the replaced file
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())
+                .map_err(|e| Error::SetPermissionsError(temp_path.to_owned(), e.to_string()))?;
+        }
+
+        file.write_all(contents)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| Error::WriteFileError(temp_path.to_owned(), e.to_string()))
     }
 }
 
@@ -64,21 +94,25 @@ impl IoAdapter for DefaultIoAdapter {
     }
 
     fn write_bytes_to_file(&self, path: &Path, contents: &[u8]) -> Result<(), Error> {
-        if !self.path_exists(path) {
-            self.create_file(path)?;
-        }
-
-        let mut file = self.open_file_write(path)?;
+        // Write through symbolic links instead of replacing them
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        let temp_path = Self::get_temp_path(&path)?;
 
         debug!(
             "Writing {} bytes to file '{}' ...",
             contents.len().to_string().color("green"),
             path.display().to_string().color("green")
         );
-        file.write_all(contents)
-            .map_err(|e| Error::WriteFileError(path.to_owned(), e.to_string()))?;
 
-        Ok(())
+        // Write to a temporary file then rename it, so that the file is never partially written
+        let result = self
+            .write_temp_file(&path, &temp_path, contents)
+            .and_then(|_| self.rename(&temp_path, &path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+
+        result
     }
 
     fn path_exists(&self, path: &Path) -> bool {
@@ -89,6 +123,12 @@ impl IoAdapter for DefaultIoAdapter {
         path.is_file()
     }
 
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        std::fs::canonicalize(path)
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_owned())
+    }
+
     fn remove_file(&self, path: &Path) -> Result<(), Error> {
         debug!(
             "Removing file '{}' ...",
@@ -108,6 +148,27 @@ impl IoAdapter for DefaultIoAdapter {
             .map_err(|e| Error::RemoveFolderError(path.to_owned(), e.to_string()))
     }
 
+    fn get_size(&self, path: &Path) -> Result<u64, Error> {
+        fs_extra::dir::get_size(path)
+            .map_err(|e| Error::GetSizeError(path.to_owned(), e.to_string()))
+    }
+
+    #[cfg(unix)]
+    fn set_executable(&self, path: &Path) -> Result<(), Error> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let map_err =
+            |e: std::io::Error| Error::SetPermissionsError(path.to_owned(), e.to_string());
+        let mut permissions = std::fs::metadata(path).map_err(map_err)?.permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+        std::fs::set_permissions(path, permissions).map_err(map_err)
+    }
+
+    #[cfg(not(unix))]
+    fn set_executable(&self, _path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error> {
         let options = fs_extra::file::CopyOptions {
             overwrite: true,
@@ -140,6 +201,41 @@ impl IoAdapter for DefaultIoAdapter {
             .map_err(|e| Error::CopyFolderError(source.into(), destination.into(), e.to_string()))
     }
 
+    fn rename(&self, source: &Path, destination: &Path) -> Result<(), Error> {
+        debug!(
+            "Renaming '{}' to '{}' ...",
+            source.display().to_string().color("green"),
+            destination.display().to_string().color("green")
+        );
+        std::fs::rename(source, destination)
+            .map_err(|e| Error::RenameError(source.into(), destination.into(), e.to_string()))
+    }
+
+    fn lock_file(&self, path: &Path) -> Result<FileLock, Error> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::CreateFolderError(parent.into(), e.to_string()))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| Error::OpenFileError(path.to_owned(), e.to_string()))?;
+
+        if file.try_lock_exclusive().is_err() {
+            debug!(
+                "Waiting for lock on file '{}' ...",
+                path.display().to_string().color("green")
+            );
+            file.lock_exclusive()
+                .map_err(|e| Error::LockFileError(path.to_owned(), e.to_string()))?;
+        }
+
+        Ok(FileLock::new(file))
+    }
+
     fn read_dir(&self, path: &Path) -> Result<ReadDir, Error> {
         debug!(
             "Reading files from directory '{}' ...",
@@ -148,6 +244,87 @@ impl IoAdapter for DefaultIoAdapter {
         std::fs::read_dir(path).map_err(|e| Error::ReadDirError(path.into(), e.to_string()))
     }
 
+    fn run_command(&self, program: &str, args: &[String], current_dir: &Path) -> Result<(), Error> {
+        let command_line = std::iter::once(program)
+            .chain(args.iter().map(|a| a.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        debug!(
+            "Running '{}' in '{}' ...",
+            command_line.color("green"),
+            current_dir.display().to_string().color("green")
+        );
+
+        let status = std::process::Command::new(program)
+            .args(args)
+            .current_dir(current_dir)
+            .status()
+            .map_err(|e| Error::CommandExecutionError(format!("{}: {}", program, e)))?;
+        if !status.success() {
+            return Err(Error::CommandFailed(command_line, status.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn run_command_with_output(
+        &self,
+        program: &Path,
+        args: &[String],
+        timeout: Duration,
+    ) -> Result<String, Error> {
+        let command_line = std::iter::once(program.to_string_lossy().to_string())
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        debug!("Running '{}' ...", command_line.color("green"));
+
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Error::CommandExecutionError(format!("{}: {}", command_line, e)))?;
+
+        // Read output in the background so that the child does not block on a full pipe
+        let mut stdout = child.stdout.take().expect("stdout should be piped");
+        let reader = std::thread::spawn(move || {
+            let mut output = vec![];
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+
+        let started_at = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started_at.elapsed() >= timeout => {
+                    // The child may have exited in the meantime
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(Error::CommandTimedOut(command_line, timeout));
+                }
+                Ok(None) => std::thread::sleep(COMMAND_POLL_INTERVAL),
+                Err(e) => {
+                    return Err(Error::CommandExecutionError(format!(
+                        "{}: {}",
+                        command_line, e
+                    )))
+                }
+            }
+        };
+
+        let output = reader
+            .join()
+            .expect("output reader should not panic")
+            .map_err(|e| Error::CommandExecutionError(format!("{}: {}", command_line, e)))?;
+        if !status.success() {
+            return Err(Error::CommandFailed(command_line, status.to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
     fn open_and_extract_zip(&self, source: &Path, destination: &Path) -> Result<(), Error> {
         let file = self.open_file_read(source)?;
 
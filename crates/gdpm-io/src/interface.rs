@@ -1,7 +1,9 @@
 use crate::error::Error;
+use crate::lock::FileLock;
 
 use std::fs::ReadDir;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// IO adapter.
 #[mockall::automock]
@@ -21,7 +23,7 @@ pub trait IoAdapter {
     /// Write string to file.
     fn write_string_to_file(&self, path: &Path, contents: &str) -> Result<(), Error>;
 
-    /// Write bytes to file.
+    /// Write bytes to file, atomically replacing it if it exists.
     fn write_bytes_to_file(&self, path: &Path, contents: &[u8]) -> Result<(), Error>;
 
     /// Remove file.
@@ -36,15 +38,42 @@ pub trait IoAdapter {
     /// Check if path is a file.
     fn path_is_file(&self, path: &Path) -> bool;
 
+    /// Get canonical path, resolving symbolic links, or absolute path if it does not exist.
+    fn canonicalize(&self, path: &Path) -> PathBuf;
+
+    /// Make file executable (no-op on non-Unix systems).
+    fn set_executable(&self, path: &Path) -> Result<(), Error>;
+
+    /// Get size in bytes of a file, or of a directory with all its contents.
+    fn get_size(&self, path: &Path) -> Result<u64, Error>;
+
     /// Copy file.
     fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error>;
 
     /// Copy directory.
     fn copy_dir(&self, source: &Path, destination: &Path) -> Result<(), Error>;
 
+    /// Rename file or directory, replacing the destination file if it exists.
+    fn rename(&self, source: &Path, destination: &Path) -> Result<(), Error>;
+
+    /// Take an exclusive advisory lock on a file (created if needed), waiting for other
+    /// processes to release it. The lock is released when the returned value is dropped.
+    fn lock_file(&self, path: &Path) -> Result<FileLock, Error>;
+
     /// Read directory contents.
     fn read_dir(&self, path: &Path) -> Result<ReadDir, Error>;
 
+    /// Run command in a folder, with inherited standard streams.
+    fn run_command(&self, program: &str, args: &[String], current_dir: &Path) -> Result<(), Error>;
+
+    /// Run command and capture its standard output, killing it if it runs longer than `timeout`.
+    fn run_command_with_output(
+        &self,
+        program: &Path,
+        args: &[String],
+        timeout: Duration,
+    ) -> Result<String, Error>;
+
     /// Open and extract ZIP file.
     fn open_and_extract_zip(&self, source: &Path, destination: &Path) -> Result<(), Error>;
 
@@ -3,7 +3,9 @@
 mod error;
 mod implementation;
 mod interface;
+mod lock;
 
 pub use crate::error::Error;
 pub use crate::implementation::DefaultIoAdapter;
 pub use crate::interface::{IoAdapter, MockIoAdapter};
+pub use crate::lock::FileLock;
@@ -0,0 +1,29 @@
+//! File locks.
+
+use std::fs::File;
+
+use fs4::FileExt;
+
+/// Advisory exclusive lock on a file, released when dropped.
+///
+/// A default value holds no lock, which is useful for mocks.
+#[derive(Debug, Default)]
+pub struct FileLock {
+    file: Option<File>,
+}
+
+impl FileLock {
+    /// Wrap an already locked file.
+    pub fn new(file: File) -> Self {
+        Self { file: Some(file) }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            // Closing the file also releases the lock
+            let _ = file.unlock();
+        }
+    }
+}
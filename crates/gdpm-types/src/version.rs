@@ -1,6 +1,7 @@
 //! Versions.
 
 use std::{
+    cmp::Ordering,
     fmt::{Display, Write},
     str::FromStr,
 };
@@ -15,6 +16,9 @@ pub enum Error {
 
     #[error("Wrong version kind: {0}")]
     WrongVersionKind(String),
+
+    #[error("Unsupported system: {0} ({1})")]
+    UnsupportedSystem(String, String),
 }
 
 /// System version.
@@ -28,8 +32,12 @@ pub enum SystemVersion {
     X1132,
     /// Linux 64-bit
     X1164,
+    /// Linux ARM 64-bit
+    LinuxArm64,
     /// MacOS
     OSX,
+    /// MacOS ARM 64-bit
+    OSXArm64,
     /// Server (Linux 64-bit)
     LinuxServer64,
     /// Headless (Linux 64-bit)
@@ -44,6 +52,20 @@ pub struct GodotVersion {
     mono: bool,
 }
 
+/// Godot engine build info, as reported by `godot --version`
+/// (e.g. `4.3.stable.mono.official.77dcf97d8`).
+///
+/// Non-official builds get a [`GodotVersionKind::Custom`] kind named after the build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GodotBuildInfo {
+    /// Engine version.
+    pub version: GodotVersion,
+    /// Build name (e.g. `official`, `custom_build`).
+    pub build: String,
+    /// Commit hash.
+    pub hash: Option<String>,
+}
+
 /// Godot version kind.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GodotVersionKind {
@@ -59,35 +81,80 @@ pub enum GodotVersionKind {
     Custom(String),
 }
 
+/// Godot version requirement.
+///
+/// Can be an exact version (`4.3`, `4.3.rc1.mono`), a wildcard (`4`, `4.x`,
+/// `4.2.x`), a caret requirement (`^4.2`), a release channel (`latest`,
+/// `stable`, `beta`) or a user-defined alias name (`lts`), optionally suffixed
+/// with `.mono`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GodotVersionReq {
+    spec: VersionReqSpec,
+    mono: bool,
+    pre_release: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VersionReqSpec {
+    Exact(GodotVersion),
+    Wildcard(Vec<u32>),
+    Caret(Vec<u32>),
+    Latest,
+    Stable,
+    Beta,
+    Alias(String),
+}
+
 impl SystemVersion {
     /// Determine system kind.
-    pub fn determine_system_kind() -> SystemVersion {
+    pub fn determine_system_kind() -> Result<SystemVersion, Error> {
+        let unsupported = || {
+            Error::UnsupportedSystem(
+                std::env::consts::OS.to_string(),
+                std::env::consts::ARCH.to_string(),
+            )
+        };
+
         if cfg!(target_os = "windows") {
             if cfg!(target_arch = "x86") {
-                SystemVersion::Win32
+                Ok(SystemVersion::Win32)
             } else if cfg!(target_arch = "x86_64") {
-                SystemVersion::Win64
+                Ok(SystemVersion::Win64)
             } else {
-                panic!("Unsupported Windows architecture.")
+                Err(unsupported())
             }
         } else if cfg!(target_os = "linux") {
             if cfg!(target_arch = "x86") {
-                SystemVersion::X1132
+                Ok(SystemVersion::X1132)
             } else if cfg!(target_arch = "x86_64") {
-                SystemVersion::X1164
+                Ok(SystemVersion::X1164)
+            } else if cfg!(target_arch = "aarch64") {
+                Ok(SystemVersion::LinuxArm64)
             } else {
-                panic!("Unsupported Linux architecture.")
+                Err(unsupported())
             }
         } else if cfg!(target_os = "macos") {
-            SystemVersion::OSX
+            if cfg!(target_arch = "aarch64") {
+                Ok(SystemVersion::OSXArm64)
+            } else {
+                Ok(SystemVersion::OSX)
+            }
         } else {
-            panic!("Unsupported OS.")
+            Err(unsupported())
         }
     }
 
     /// Check if system is linux-based.
     pub fn is_linux(&self) -> bool {
-        matches!(self, SystemVersion::X1132 | SystemVersion::X1164)
+        matches!(
+            self,
+            SystemVersion::X1132 | SystemVersion::X1164 | SystemVersion::LinuxArm64
+        )
+    }
+
+    /// Check if system is macOS-based.
+    pub fn is_macos(&self) -> bool {
+        matches!(self, SystemVersion::OSX | SystemVersion::OSXArm64)
     }
 
     /// Get executable extension for current system.
@@ -95,65 +162,94 @@ impl SystemVersion {
         match self {
             SystemVersion::Win32 | SystemVersion::Win64 => "exe",
             SystemVersion::X1132 | SystemVersion::X1164 => "x11",
+            SystemVersion::LinuxArm64 => "arm64",
             SystemVersion::LinuxHeadless64 => "headless.x11",
             SystemVersion::LinuxServer64 => "server.x11",
-            SystemVersion::OSX => "osx",
+            SystemVersion::OSX | SystemVersion::OSXArm64 => "osx",
         }
     }
 
-    /// Get archive basename for current system.
-    pub fn get_archive_basename(&self, with_mono: bool) -> &'static str {
-        match self {
-            SystemVersion::Win32 => {
-                if with_mono {
-                    "mono_win32"
-                } else {
-                    "win32.exe"
+    /// Get archive basename for current system and version.
+    ///
+    /// Fails if there is no official build of this version for current system.
+    pub fn get_archive_basename(&self, version: &GodotVersion) -> Result<&'static str, Error> {
+        self.get_archive_names(version).map(|n| n.0)
+    }
+
+    /// Get basename of the executable in the archive, for current system and version.
+    ///
+    /// MacOS archives contain an app bundle instead.
+    pub fn get_executable_basename(&self, version: &GodotVersion) -> Result<&'static str, Error> {
+        self.get_archive_names(version).map(|n| n.1)
+    }
+
+    fn get_archive_names(
+        &self,
+        version: &GodotVersion,
+    ) -> Result<(&'static str, &'static str), Error> {
+        let mono = version.mono();
+
+        let names = if version.major() >= 4 {
+            // Godot 4 has no server / headless builds, use the `--headless` argument instead.
+            match self {
+                SystemVersion::Win32 if mono => ("mono_win32", "mono_win32.exe"),
+                SystemVersion::Win32 => ("win32.exe", "win32.exe"),
+                SystemVersion::Win64 if mono => ("mono_win64", "mono_win64.exe"),
+                SystemVersion::Win64 => ("win64.exe", "win64.exe"),
+                SystemVersion::X1132 if mono => ("mono_linux_x86_32", "mono_linux.x86_32"),
+                SystemVersion::X1132 => ("linux.x86_32", "linux.x86_32"),
+                SystemVersion::X1164
+                | SystemVersion::LinuxServer64
+                | SystemVersion::LinuxHeadless64
+                    if mono =>
+                {
+                    ("mono_linux_x86_64", "mono_linux.x86_64")
                 }
-            }
-            SystemVersion::Win64 => {
-                if with_mono {
-                    "mono_win64"
-                } else {
-                    "win64.exe"
+                SystemVersion::X1164
+                | SystemVersion::LinuxServer64
+                | SystemVersion::LinuxHeadless64 => ("linux.x86_64", "linux.x86_64"),
+                SystemVersion::LinuxArm64 if mono => ("mono_linux_arm64", "mono_linux.arm64"),
+                SystemVersion::LinuxArm64 => ("linux.arm64", "linux.arm64"),
+                SystemVersion::OSX | SystemVersion::OSXArm64 if mono => {
+                    ("mono_macos.universal", "mono_macos.universal")
                 }
-            }
-            SystemVersion::X1132 => {
-                if with_mono {
-                    "mono_x11_32"
-                } else {
-                    "x11.32"
+                SystemVersion::OSX | SystemVersion::OSXArm64 => {
+                    ("macos.universal", "macos.universal")
                 }
             }
-            SystemVersion::X1164 => {
-                if with_mono {
-                    "mono_x11_64"
-                } else {
-                    "x11.64"
+        } else {
+            match self {
+                SystemVersion::Win32 if mono => ("mono_win32", "mono_win32.exe"),
+                SystemVersion::Win32 => ("win32.exe", "win32.exe"),
+                SystemVersion::Win64 if mono => ("mono_win64", "mono_win64.exe"),
+                SystemVersion::Win64 => ("win64.exe", "win64.exe"),
+                SystemVersion::X1132 if mono => ("mono_x11_32", "mono_x11.32"),
+                SystemVersion::X1132 => ("x11.32", "x11.32"),
+                SystemVersion::X1164 if mono => ("mono_x11_64", "mono_x11.64"),
+                SystemVersion::X1164 => ("x11.64", "x11.64"),
+                // No official ARM builds for Godot 3
+                SystemVersion::LinuxArm64 => {
+                    return Err(Error::UnsupportedSystem(
+                        self.to_string(),
+                        format!("no official build for Godot Engine {}", version),
+                    ))
                 }
-            }
-            SystemVersion::OSX => {
-                if with_mono {
-                    "mono_osx.64"
-                } else {
-                    "osx.universal"
+                SystemVersion::OSX | SystemVersion::OSXArm64 if mono => {
+                    ("mono_osx.64", "mono_osx.64")
                 }
-            }
-            SystemVersion::LinuxServer64 => {
-                if with_mono {
-                    "mono_linux_server_64"
-                } else {
-                    "linux_server.64"
+                SystemVersion::OSX | SystemVersion::OSXArm64 => ("osx.universal", "osx.universal"),
+                SystemVersion::LinuxServer64 if mono => {
+                    ("mono_linux_server_64", "mono_linux_server.64")
                 }
-            }
-            SystemVersion::LinuxHeadless64 => {
-                if with_mono {
-                    "mono_linux_headless_64"
-                } else {
-                    "linux_server.64"
+                SystemVersion::LinuxServer64 => ("linux_server.64", "linux_server.64"),
+                SystemVersion::LinuxHeadless64 if mono => {
+                    ("mono_linux_headless_64", "mono_linux_headless.64")
                 }
+                SystemVersion::LinuxHeadless64 => ("linux_headless.64", "linux_headless.64"),
             }
-        }
+        };
+
+        Ok(names)
     }
 }
 
@@ -164,7 +260,9 @@ impl Display for SystemVersion {
             Self::Win64 => write!(f, "win64"),
             Self::X1132 => write!(f, "linux32"),
             Self::X1164 => write!(f, "linux64"),
+            Self::LinuxArm64 => write!(f, "linuxarm64"),
             Self::OSX => write!(f, "osx"),
+            Self::OSXArm64 => write!(f, "osxarm64"),
             Self::LinuxServer64 => write!(f, "linuxserver64"),
             Self::LinuxHeadless64 => write!(f, "linuxheadless64"),
         }
@@ -196,6 +294,28 @@ impl GodotVersion {
         self.mono
     }
 
+    /// Get major version number (0 if unknown).
+    pub fn major(&self) -> u16 {
+        self.version
+            .split('.')
+            .next()
+            .and_then(|m| m.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Get numeric version components (non-numeric components count as 0).
+    pub fn numbers(&self) -> Vec<u32> {
+        self.version
+            .split('.')
+            .map(|n| n.parse().unwrap_or(0))
+            .collect()
+    }
+
+    /// Is this a stable release?
+    pub fn is_stable(&self) -> bool {
+        self.kind == GodotVersionKind::Stable
+    }
+
     /// Get version slug.
     pub fn slug(&self) -> String {
         slugify!(&self.to_string())
@@ -217,11 +337,10 @@ impl GodotVersion {
                 .get("version")
                 .and_then(|x| x.to_str())
                 .unwrap_or_else(|| String::from("unknown"));
-            let kind: GodotVersionKind = map
-                .get("kind")
-                .and_then(|x| x.to_str())
-                .map(|x| GodotVersionKind::from_str(&x).unwrap())
-                .unwrap_or(GodotVersionKind::Stable);
+            let kind = match map.get("kind").and_then(|x| x.to_str()) {
+                Some(kind) => GodotVersionKind::from_str(&kind).ok()?,
+                None => GodotVersionKind::Stable,
+            };
             let mono = map.get("mono").and_then(|x| x.to_bool()).unwrap_or(false);
 
             Some(Self {
@@ -264,6 +383,32 @@ impl Display for GodotVersion {
     }
 }
 
+/// Compare version numbers, missing components counting as 0.
+fn compare_numbers(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    let component = |v: &[u32], i| v.get(i).copied().unwrap_or(0);
+
+    (0..lhs.len().max(rhs.len()))
+        .map(|i| component(lhs, i).cmp(&component(rhs, i)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+impl Ord for GodotVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_numbers(&self.numbers(), &other.numbers())
+            .then_with(|| self.kind.cmp(&other.kind))
+            .then_with(|| self.mono.cmp(&other.mono))
+            // Keep ordering consistent with equality (e.g. "4.2" and "4.2.0")
+            .then_with(|| self.version.cmp(&other.version))
+    }
+}
+
+impl PartialOrd for GodotVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FromStr for GodotVersion {
     type Err = Error;
 
@@ -277,7 +422,10 @@ impl FromStr for GodotVersion {
             parts.pop();
         }
 
-        if let Some(&x) = parts.last() {
+        if let Some(idx) = parts.iter().position(|&p| p == "custom") {
+            kind = GodotVersionKind::Custom(parts[idx + 1..].join("."));
+            parts.truncate(idx);
+        } else if let Some(&x) = parts.last() {
             if x.parse::<u16>().is_err() {
                 kind = GodotVersionKind::from_str(x)?;
                 parts.pop();
@@ -317,6 +465,269 @@ impl Display for GodotVersionKind {
     }
 }
 
+impl GodotVersionKind {
+    // Custom < alpha < beta < rc < stable
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Custom(_) => 0,
+            Self::Alpha(_) => 1,
+            Self::Beta(_) => 2,
+            Self::ReleaseCandidate(_) => 3,
+            Self::Stable => 4,
+        }
+    }
+}
+
+impl Ord for GodotVersionKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.precedence()
+            .cmp(&other.precedence())
+            .then_with(|| match (self, other) {
+                (Self::Alpha(a), Self::Alpha(b))
+                | (Self::Beta(a), Self::Beta(b))
+                | (Self::ReleaseCandidate(a), Self::ReleaseCandidate(b)) => a.cmp(b),
+                (Self::Custom(a), Self::Custom(b)) => a.cmp(b),
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for GodotVersionKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl GodotVersionReq {
+    /// Allow pre-releases (alpha, beta, rc) to match non-exact requirements.
+    pub fn with_pre_release(mut self, pre_release: bool) -> Self {
+        self.pre_release = pre_release;
+        self
+    }
+
+    /// Get exact version, if the requirement is exact.
+    pub fn exact(&self) -> Option<&GodotVersion> {
+        match &self.spec {
+            VersionReqSpec::Exact(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Require (or not) a mono version.
+    pub fn with_mono(mut self, mono: bool) -> Self {
+        if let VersionReqSpec::Exact(v) = &mut self.spec {
+            v.mono = mono;
+        }
+        self.mono = mono;
+        self
+    }
+
+    /// Is mono required?
+    pub fn mono(&self) -> bool {
+        self.mono
+    }
+
+    /// Are pre-releases allowed?
+    pub fn pre_release(&self) -> bool {
+        self.pre_release
+    }
+
+    /// Get alias name, if the requirement is an alias.
+    pub fn alias(&self) -> Option<&str> {
+        match &self.spec {
+            VersionReqSpec::Alias(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Is the requirement a release channel (`latest`, `stable` or `beta`)?
+    pub fn is_channel(&self) -> bool {
+        matches!(
+            self.spec,
+            VersionReqSpec::Latest | VersionReqSpec::Stable | VersionReqSpec::Beta
+        )
+    }
+
+    /// Check if a version matches the requirement.
+    ///
+    /// Aliases never match, they need to be expanded first.
+    pub fn matches(&self, version: &GodotVersion) -> bool {
+        match &self.spec {
+            VersionReqSpec::Exact(v) => return v == version,
+            VersionReqSpec::Alias(_) => return false,
+            VersionReqSpec::Stable => return version.mono == self.mono && version.is_stable(),
+            VersionReqSpec::Beta => {
+                return version.mono == self.mono
+                    && !matches!(version.kind, GodotVersionKind::Custom(_))
+            }
+            _ => (),
+        }
+
+        if version.mono != self.mono || (!self.pre_release && !version.is_stable()) {
+            return false;
+        }
+
+        let numbers = version.numbers();
+        match &self.spec {
+            VersionReqSpec::Exact(_)
+            | VersionReqSpec::Alias(_)
+            | VersionReqSpec::Stable
+            | VersionReqSpec::Beta => unreachable!(),
+            VersionReqSpec::Wildcard(prefix) => numbers.starts_with(prefix),
+            VersionReqSpec::Caret(minimum) => {
+                numbers.first() == minimum.first() && compare_numbers(&numbers, minimum).is_ge()
+            }
+            VersionReqSpec::Latest => true,
+        }
+    }
+
+    /// Resolve the highest matching version from a list.
+    pub fn resolve<'a, T: IntoIterator<Item = &'a GodotVersion>>(
+        &self,
+        versions: T,
+    ) -> Option<GodotVersion> {
+        versions
+            .into_iter()
+            .filter(|v| self.matches(v))
+            .max()
+            .cloned()
+    }
+}
+
+impl From<GodotVersion> for GodotVersionReq {
+    fn from(version: GodotVersion) -> Self {
+        Self {
+            mono: version.mono,
+            spec: VersionReqSpec::Exact(version),
+            pre_release: false,
+        }
+    }
+}
+
+impl Display for GodotVersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        match &self.spec {
+            VersionReqSpec::Exact(v) => return write!(f, "{}", v),
+            VersionReqSpec::Wildcard(n) => write!(f, "{}.x", join(n))?,
+            VersionReqSpec::Caret(n) => write!(f, "^{}", join(n))?,
+            VersionReqSpec::Latest => write!(f, "latest")?,
+            VersionReqSpec::Stable => write!(f, "stable")?,
+            VersionReqSpec::Beta => write!(f, "beta")?,
+            VersionReqSpec::Alias(name) => write!(f, "{}", name)?,
+        }
+
+        if self.mono {
+            write!(f, ".mono")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GodotVersionReq {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (req, mono) = match s.strip_suffix(".mono") {
+            Some(r) => (r, true),
+            None => (s, false),
+        };
+        let parse_numbers = |r: &str| -> Result<Vec<u32>, Error> {
+            r.split('.')
+                .map(|n| n.parse().map_err(|_| Error::WrongVersion(s.to_string())))
+                .collect()
+        };
+
+        let spec = if req == "latest" {
+            VersionReqSpec::Latest
+        } else if req == "stable" {
+            VersionReqSpec::Stable
+        } else if req == "beta" {
+            VersionReqSpec::Beta
+        } else if is_alias_name(req) {
+            VersionReqSpec::Alias(req.to_string())
+        } else if let Some(r) = req.strip_prefix('^') {
+            VersionReqSpec::Caret(parse_numbers(r)?)
+        } else if let Some(r) = req.strip_suffix(".x").or(req.strip_suffix(".*")) {
+            VersionReqSpec::Wildcard(parse_numbers(r)?)
+        } else if !req.contains('.') {
+            VersionReqSpec::Wildcard(parse_numbers(req)?)
+        } else {
+            return Ok(GodotVersion::from_str(s)?.into());
+        };
+
+        Ok(Self {
+            spec,
+            mono,
+            pre_release: false,
+        })
+    }
+}
+
+/// Check if a name can be used as a version alias (e.g. `lts`, `team-4`).
+///
+/// Alias names start with a letter, and only contain letters, digits, `-` and `_`.
+pub fn is_alias_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !["latest", "stable", "beta", "mono"].contains(&name)
+}
+
+impl TryFrom<&str> for GodotVersionReq {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl FromStr for GodotBuildInfo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('.').peekable();
+
+        let mut numbers = vec![];
+        while let Some(n) = parts.next_if(|p| p.parse::<u16>().is_ok()) {
+            numbers.push(n);
+        }
+        if numbers.is_empty() {
+            return Err(Error::WrongVersion(s.to_string()));
+        }
+
+        let kind = parts
+            .next()
+            .ok_or_else(|| Error::WrongVersion(s.to_string()))?;
+        let mono = parts.next_if_eq(&"mono").is_some();
+        let build = parts.next().unwrap_or("official").to_string();
+        let hash = parts.next().map(str::to_string);
+
+        let kind = if build == "official" {
+            // Unknown official kinds (e.g. dev snapshots) are kept as custom
+            GodotVersionKind::from_str(kind)
+                .unwrap_or_else(|_| GodotVersionKind::Custom(kind.to_string()))
+        } else {
+            GodotVersionKind::Custom(build.clone())
+        };
+
+        Ok(Self {
+            version: GodotVersion::new(&numbers.join("."), kind, mono),
+            build,
+            hash,
+        })
+    }
+}
+
 impl FromStr for GodotVersionKind {
     type Err = Error;
 
@@ -413,6 +824,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_custom_version() {
+        let version = GodotVersion::new("4.3", GodotVersionKind::Custom("my_build".into()), true);
+        assert_eq!(version.to_string(), "4.3.custom.my_build.mono");
+        assert_eq!(
+            GodotVersion::from_str("4.3.custom.my_build.mono").unwrap(),
+            version
+        );
+    }
+
+    #[test]
+    fn test_parse_build_info() {
+        let info = GodotBuildInfo::from_str("4.3.stable.mono.official.77dcf97d8\n").unwrap();
+        assert_eq!(info.version, GodotVersion::from_str("4.3.mono").unwrap());
+        assert_eq!(info.build, "official");
+        assert_eq!(info.hash.as_deref(), Some("77dcf97d8"));
+
+        let info = GodotBuildInfo::from_str("3.5.2.rc1.official.71facbb3b").unwrap();
+        assert_eq!(info.version, GodotVersion::from_str("3.5.2.rc1").unwrap());
+
+        let info = GodotBuildInfo::from_str("4.4.dev.custom_build.abcdef").unwrap();
+        assert_eq!(
+            info.version,
+            GodotVersion::new(
+                "4.4",
+                GodotVersionKind::Custom("custom_build".into()),
+                false
+            )
+        );
+
+        assert!(GodotBuildInfo::from_str("Godot Engine").is_err());
+        assert!(GodotBuildInfo::from_str("4.3").is_err());
+    }
+
     #[test]
     fn test_export_template_name() {
         assert_eq!(
@@ -428,4 +873,156 @@ mod tests {
             "3.1.2.stable.mono"
         );
     }
+
+    #[test]
+    fn test_major() {
+        assert_eq!(GodotVersion::from_str("3.1.2").unwrap().major(), 3);
+        assert_eq!(GodotVersion::from_str("4.3.mono").unwrap().major(), 4);
+        assert_eq!(
+            GodotVersion::new("custom", GodotVersionKind::Stable, false).major(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_ordering() {
+        let v = |s: &str| GodotVersion::from_str(s).unwrap();
+
+        assert!(v("4.2") < v("4.10"));
+        assert!(v("4.2") < v("4.2.1"));
+        assert!(v("3.6") < v("4.0.alpha1"));
+        assert!(v("4.3.alpha2") < v("4.3.beta1"));
+        assert!(v("4.3.beta3") < v("4.3.rc1"));
+        assert!(v("4.3.rc1") < v("4.3.rc2"));
+        assert!(v("4.3.rc2") < v("4.3"));
+        assert!(v("4.3") < v("4.3.mono"));
+        assert!(v("4.2") < v("4.2.0"));
+        assert_eq!(v("4.2").cmp(&v("4.2")), Ordering::Equal);
+
+        let mut versions = vec![v("4.10"), v("4.2.rc1"), v("3.5"), v("4.2")];
+        versions.sort();
+        assert_eq!(versions, vec![v("3.5"), v("4.2.rc1"), v("4.2"), v("4.10")]);
+    }
+
+    #[test]
+    fn test_parse_version_req() {
+        let req = |s: &str| GodotVersionReq::from_str(s).unwrap();
+
+        assert_eq!(
+            req("4.3.rc1.mono").exact(),
+            Some(&GodotVersion::from_str("4.3.rc1.mono").unwrap())
+        );
+        assert_eq!(req("4").to_string(), "4.x");
+        assert_eq!(req("4.2.x").to_string(), "4.2.x");
+        assert_eq!(req("^4.2.mono").to_string(), "^4.2.mono");
+        assert_eq!(req("latest").to_string(), "latest");
+        assert_eq!(req("stable.mono").to_string(), "stable.mono");
+        assert!(req("beta").is_channel());
+        assert_eq!(req("lts").alias(), Some("lts"));
+        assert_eq!(req("team-4.mono").alias(), Some("team-4"));
+        assert!(req("team-4.mono").mono());
+        assert_eq!(req("4.3").with_mono(true).to_string(), "4.3.mono");
+        assert!(GodotVersionReq::from_str("^4.a").is_err());
+        assert!(GodotVersionReq::from_str("lts!").is_err());
+        assert!(GodotVersionReq::from_str("4.3.unknown").is_err());
+    }
+
+    #[test]
+    fn test_resolve_version_req() {
+        let versions: Vec<_> = ["3.6", "4.2", "4.2.2", "4.3", "4.10.beta1", "4.3.mono"]
+            .into_iter()
+            .map(|v| GodotVersion::from_str(v).unwrap())
+            .collect();
+        let resolve = |s: &str, pre_release| {
+            GodotVersionReq::from_str(s)
+                .unwrap()
+                .with_pre_release(pre_release)
+                .resolve(&versions)
+                .map(|v| v.to_string())
+        };
+
+        assert_eq!(resolve("latest", false).as_deref(), Some("4.3"));
+        assert_eq!(resolve("latest", true).as_deref(), Some("4.10.beta1"));
+        assert_eq!(resolve("stable", true).as_deref(), Some("4.3"));
+        assert_eq!(resolve("beta", false).as_deref(), Some("4.10.beta1"));
+        assert_eq!(resolve("stable.mono", false).as_deref(), Some("4.3.mono"));
+        assert_eq!(resolve("lts", false), None);
+        assert_eq!(resolve("4.x", false).as_deref(), Some("4.3"));
+        assert_eq!(resolve("4.2.x", false).as_deref(), Some("4.2.2"));
+        assert_eq!(resolve("3", false).as_deref(), Some("3.6"));
+        assert_eq!(resolve("^4.2.1", false).as_deref(), Some("4.3"));
+        assert_eq!(resolve("^4.4", false), None);
+        assert_eq!(resolve("4.x.mono", false).as_deref(), Some("4.3.mono"));
+        assert_eq!(resolve("4.2", false).as_deref(), Some("4.2"));
+        assert_eq!(resolve("5", false), None);
+    }
+
+    #[test]
+    fn test_archive_basename() {
+        let check = |version: &str, system: SystemVersion, archive, executable| {
+            let version = GodotVersion::from_str(version).unwrap();
+            assert_eq!(system.get_archive_basename(&version).unwrap(), archive);
+            assert_eq!(
+                system.get_executable_basename(&version).unwrap(),
+                executable
+            );
+        };
+
+        check("3.6", SystemVersion::X1164, "x11.64", "x11.64");
+        check(
+            "3.6.mono",
+            SystemVersion::X1164,
+            "mono_x11_64",
+            "mono_x11.64",
+        );
+        check("3.6", SystemVersion::OSX, "osx.universal", "osx.universal");
+        check(
+            "3.6",
+            SystemVersion::LinuxServer64,
+            "linux_server.64",
+            "linux_server.64",
+        );
+        check("4.3", SystemVersion::X1164, "linux.x86_64", "linux.x86_64");
+        check(
+            "4.3.mono",
+            SystemVersion::X1164,
+            "mono_linux_x86_64",
+            "mono_linux.x86_64",
+        );
+        check(
+            "4.3",
+            SystemVersion::LinuxArm64,
+            "linux.arm64",
+            "linux.arm64",
+        );
+        check(
+            "4.3",
+            SystemVersion::OSXArm64,
+            "macos.universal",
+            "macos.universal",
+        );
+        check("4.3", SystemVersion::Win64, "win64.exe", "win64.exe");
+        check(
+            "4.3.mono",
+            SystemVersion::Win64,
+            "mono_win64",
+            "mono_win64.exe",
+        );
+    }
+
+    #[test]
+    fn test_archive_basename_unsupported() {
+        // No official ARM builds for Godot 3
+        for version in ["3.6", "3.6.mono"] {
+            let version = GodotVersion::from_str(version).unwrap();
+            assert!(matches!(
+                SystemVersion::LinuxArm64.get_archive_basename(&version),
+                Err(Error::UnsupportedSystem(..))
+            ));
+            assert!(matches!(
+                SystemVersion::LinuxArm64.get_executable_basename(&version),
+                Err(Error::UnsupportedSystem(..))
+            ));
+        }
+    }
 }
@@ -6,8 +6,10 @@ use tracing_subscriber::EnvFilter;
 use crate::context::Context;
 
 use super::dependencies;
+use super::doctor;
 use super::engine;
 use super::project;
+use super::shim;
 
 /// Manage Godot versions and project dependencies
 #[derive(Parser)]
@@ -39,6 +41,13 @@ enum Command {
         #[clap(subcommand)]
         command: ProjectCommand,
     },
+    /// Check gdpm configuration, and repair it
+    Doctor(doctor::Doctor),
+    /// Manage the `godot` shim
+    Shim {
+        #[clap(subcommand)]
+        command: ShimCommand,
+    },
 }
 
 #[derive(Subcommand)]
@@ -50,12 +59,40 @@ enum EngineCommand {
     Remove(engine::remove::Remove),
     /// Show or set default engine
     Default(engine::default::Default),
+    /// Show, set or unset engine aliases
+    Alias(engine::alias::Alias),
     /// List engines
     List(engine::list::List),
     /// List engines from remote
     ListRemote(engine::list_remote::ListRemote),
     /// Run command on engine
     Run(engine::run::Run),
+    /// Find Godot executables in a folder and register them
+    Scan(engine::scan::Scan),
+    /// Show disk usage of engines and their export templates
+    Du(engine::du::Du),
+    /// Remove installed engines which are not used anymore
+    Prune(engine::prune::Prune),
+    /// Build engine from source with SCons, and register it as a custom version
+    Build(engine::build::Build),
+    /// Manage export templates
+    Templates {
+        #[clap(subcommand)]
+        command: EngineTemplatesCommand,
+    },
+}
+
+#[derive(Subcommand)]
+#[clap(disable_version_flag = true)]
+enum EngineTemplatesCommand {
+    /// Download and install export templates from official mirror or specific URL / path
+    Add(engine::templates::Add),
+    /// Uninstall export templates
+    Remove(engine::templates::Remove),
+    /// List installed export templates
+    List(engine::templates::List),
+    /// Check installed export templates
+    Verify(engine::templates::Verify),
 }
 
 #[derive(Subcommand)]
@@ -76,6 +113,8 @@ enum ProjectCommand {
     New(project::new::New),
     /// Edit project using associated engine version
     Edit(project::edit::Edit),
+    /// Export project using associated engine version
+    Export(project::export::Export),
     /// Show project info
     Info(project::info::Info),
     /// Run project using associated engine version
@@ -84,9 +123,39 @@ enum ProjectCommand {
     SetEngine(project::set_engine::SetEngine),
     /// Unset associated engine version
     UnsetEngine(project::unset_engine::UnsetEngine),
+    /// Show or bump project version
+    Version(project::version::Version),
+    /// Manage project settings
+    Config {
+        #[clap(subcommand)]
+        command: ProjectConfigCommand,
+    },
 }
 
-pub fn parse_args<I: IoAdapter, D: DownloadAdapter>(
+#[derive(Subcommand)]
+#[clap(disable_version_flag = true)]
+enum ProjectConfigCommand {
+    /// Show a project setting value
+    Get(project::config::Get),
+    /// Set a project setting value
+    Set(project::config::Set),
+    /// Unset a project setting
+    Unset(project::config::Unset),
+}
+
+#[derive(Subcommand)]
+#[clap(disable_version_flag = true)]
+enum ShimCommand {
+    /// Install a `godot` shim running the engine associated to the current project
+    Install(shim::Install),
+    /// Remove the `godot` shim
+    Uninstall(shim::Uninstall),
+    /// Run the engine associated to the current project (called by the shim)
+    #[clap(hide = true)]
+    Exec(shim::Exec),
+}
+
+pub fn parse_args<I: IoAdapter + Clone + Send + 'static, D: DownloadAdapter>(
     context: Context<I, D>,
     args: Args,
 ) -> Result<()> {
@@ -112,9 +181,20 @@ pub fn parse_args<I: IoAdapter, D: DownloadAdapter>(
             EngineCommand::Add(c) => c.execute(&context),
             EngineCommand::Remove(c) => c.execute(&context),
             EngineCommand::Default(c) => c.execute(&context),
+            EngineCommand::Alias(c) => c.execute(&context),
             EngineCommand::List(c) => c.execute(&context),
             EngineCommand::Run(c) => c.execute(&context),
             EngineCommand::ListRemote(c) => c.execute(&context),
+            EngineCommand::Scan(c) => c.execute(&context),
+            EngineCommand::Du(c) => c.execute(&context),
+            EngineCommand::Prune(c) => c.execute(&context),
+            EngineCommand::Build(c) => c.execute(&context),
+            EngineCommand::Templates { command } => match command {
+                EngineTemplatesCommand::Add(c) => c.execute(&context),
+                EngineTemplatesCommand::Remove(c) => c.execute(&context),
+                EngineTemplatesCommand::List(c) => c.execute(&context),
+                EngineTemplatesCommand::Verify(c) => c.execute(&context),
+            },
         },
         Command::Deps { command } => match command {
             DependenciesCommand::Add(c) => c.execute(&context),
@@ -123,11 +203,24 @@ pub fn parse_args<I: IoAdapter, D: DownloadAdapter>(
         },
         Command::Project { command } => match command {
             ProjectCommand::Edit(c) => c.execute(&context),
+            ProjectCommand::Export(c) => c.execute(&context),
             ProjectCommand::Info(c) => c.execute(&context),
             ProjectCommand::New(c) => c.execute(&context),
             ProjectCommand::Run(c) => c.execute(&context),
             ProjectCommand::SetEngine(c) => c.execute(&context),
             ProjectCommand::UnsetEngine(c) => c.execute(&context),
+            ProjectCommand::Version(c) => c.execute(&context),
+            ProjectCommand::Config { command } => match command {
+                ProjectConfigCommand::Get(c) => c.execute(&context),
+                ProjectConfigCommand::Set(c) => c.execute(&context),
+                ProjectConfigCommand::Unset(c) => c.execute(&context),
+            },
+        },
+        Command::Doctor(c) => c.execute(&context),
+        Command::Shim { command } => match command {
+            ShimCommand::Install(c) => c.execute(&context),
+            ShimCommand::Uninstall(c) => c.execute(&context),
+            ShimCommand::Exec(c) => c.execute(&context),
         },
     }
 }
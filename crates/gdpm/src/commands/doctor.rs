@@ -0,0 +1,70 @@
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    engine::EngineHandler,
+    error::{ConfigError, EngineError},
+    io::{write_stderr, write_stdout, IoAdapter},
+};
+
+use crate::context::Context;
+
+/// Check gdpm configuration, and repair it
+#[derive(Parser)]
+pub struct Doctor {
+    /// Repair found issues, removing invalid entries
+    #[clap(long)]
+    fix: bool,
+}
+
+impl Doctor {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let issues = match ehandler.check() {
+            Ok(issues) => issues,
+            Err(EngineError::ConfigError(ConfigError::MalformedSettings(e))) => {
+                write_stderr!(
+                    context.io(),
+                    "{}\n{}\n",
+                    "gdpm configuration file is malformed, it needs to be fixed by hand."
+                        .color("red"),
+                    e
+                )?;
+                std::process::exit(1);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if issues.is_empty() {
+            write_stdout!(context.io(), "{}\n", "No issue found.".color("green"))?;
+            return Ok(());
+        }
+
+        for issue in &issues {
+            write_stdout!(
+                context.io(),
+                "{} {}\n",
+                "-".color("yellow"),
+                issue.to_string().color("yellow")
+            )?;
+        }
+
+        if self.fix {
+            ehandler.repair(&issues)?;
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                format!("Fixed {} issue(s).", issues.len()).color("green")
+            )?;
+        } else {
+            write_stdout!(
+                context.io(),
+                "Use `gdpm doctor --fix` to repair the configuration.\n"
+            )?;
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
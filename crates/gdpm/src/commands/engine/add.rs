@@ -1,26 +1,42 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use color_eyre::Result;
 use colored::Colorize;
+use futures_util::future::join_all;
 use gdpm_core::{
-    downloader::{download::Downloader, error::DownloadError, DownloadAdapter},
+    config::{GlobalConfig, UserDir},
+    downloader::{
+        checksum::{Checksum, Checksums, OFFICIAL_CHECKSUMS_FILENAME},
+        download::Downloader,
+        error::DownloadError,
+        DownloadAdapter,
+    },
     engine::{EngineHandler, EngineInfo},
-    io::{write_stderr, write_stdout, IoAdapter},
-    types::version::{GodotVersion, SystemVersion},
+    error::{ConfigError, EngineError},
+    io::{write_stderr, write_stdout, FileLock, IoAdapter},
+    types::version::{GodotVersion, GodotVersionReq, SystemVersion},
 };
 use tracing::info;
 
-use crate::{common::parse_godot_version_args, context::Context};
-
-pub(crate) const MIRROR_URL: &str =
-    "https://github.com/godotengine/godot-builds/releases/download/";
+use crate::{
+    commands::engine::list_remote::ListRemote,
+    common::{expand_alias_or_exit, parse_godot_version_args, print_build_mismatch_warning},
+    context::Context,
+};
 
-/// Download and install engine from official mirror or specific URL / path (e.g. 3.3.4, 3.3.4.mono, 3.5.rc1, 3.5.rc1.mono)
+/// Download and install engines from official mirror or specific URL / path (e.g. 3.3.4, 3.3.4.mono, 3.5.rc1, 3.5.rc1.mono, 4.x, ^4.2, latest)
 #[derive(Parser)]
 pub(crate) struct Add {
-    /// Engine version
-    pub(crate) engine: GodotVersion,
+    /// Engine versions or requirements
+    #[clap(required = true)]
+    pub(crate) engine: Vec<GodotVersionReq>,
+    /// Allow pre-release versions when resolving engine requirements
+    #[clap(long)]
+    pub(crate) pre_release: bool,
+    /// Ignore cached remote versions when resolving engine requirements
+    #[clap(long)]
+    pub(crate) refresh: bool,
     /// Headless?
     #[clap(long)]
     pub(crate) headless: bool,
@@ -33,135 +49,458 @@ pub(crate) struct Add {
     /// Target path
     #[clap(long)]
     pub(crate) target_path: Option<PathBuf>,
+    /// Expected SHA-256 checksum of the archive at target URL
+    #[clap(long, requires = "target_url", conflicts_with = "sha512")]
+    pub(crate) sha256: Option<String>,
+    /// Expected SHA-512 checksum of the archive at target URL
+    #[clap(long, requires = "target_url")]
+    pub(crate) sha512: Option<String>,
+    /// Download mirror URL, tried before configured mirrors (can be repeated)
+    #[clap(long, value_parser = parse_mirror_url, conflicts_with = "target_url")]
+    pub(crate) mirror: Vec<String>,
+    /// Install without verifying official checksums, when they are unavailable (not recommended)
+    #[clap(long, conflicts_with = "target_url")]
+    pub(crate) skip_checksum: bool,
     /// Allow overwrite
     #[clap(long)]
     pub(crate) overwrite: bool,
 }
 
+fn parse_mirror_url(url: &str) -> Result<String, DownloadError> {
+    Downloader::parse_mirror_url(url)
+}
+
+/// Engine or export templates installation error
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum InstallError {
+    #[error("{0} (or wrong url: {1})")]
+    NotFound(String, String),
+    #[error("Checksum mismatch for file '{name}', refusing to install.\n    | expected: {expected}\n    | actual:   {actual}")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Could not fetch official checksums, refusing to install. Use `--skip-checksum` to install without verification.\n    | {0}")]
+    MissingOfficialChecksums(#[source] DownloadError),
+    #[error("No official checksum found for file '{0}', refusing to install. Use `--skip-checksum` to install without verification.")]
+    MissingOfficialChecksum(String),
+    #[error("Unexpected error while trying to download file '{0}'\n    | {1}")]
+    DownloadFailed(String, #[source] DownloadError),
+    #[error(transparent)]
+    EngineError(#[from] EngineError),
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    IoError(#[from] gdpm_core::io::Error),
+    #[error(transparent)]
+    VersionError(#[from] gdpm_core::types::version::Error),
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+}
+
+impl InstallError {
+    fn from_download_error(error: DownloadError, name: &str, not_found_message: &str) -> Self {
+        match error {
+            DownloadError::NotFound(u) => Self::NotFound(not_found_message.to_string(), u),
+            DownloadError::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            } => Self::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            },
+            e => Self::DownloadFailed(name.to_string(), e),
+        }
+    }
+}
+
 impl Add {
-    pub(crate) async fn download_file_at_url<I: IoAdapter, D: DownloadAdapter>(
+    /// Lock a downloaded file, waiting on the blocking thread pool for other gdpm processes.
+    async fn lock_download<I: IoAdapter + Clone + Send + 'static>(
+        io: &I,
+        target: &Path,
+    ) -> Result<FileLock, InstallError> {
+        let (io, target) = (io.clone(), target.to_path_buf());
+        Ok(tokio::task::spawn_blocking(move || UserDir::new(&io).lock_file(&target)).await??)
+    }
+
+    pub(crate) async fn download_file_from_urls<
+        I: IoAdapter + Clone + Send + 'static,
+        D: DownloadAdapter,
+    >(
         context: &Context<I, D>,
-        url: &str,
+        urls: &[String],
         version: GodotVersion,
         system: SystemVersion,
-    ) -> Result<()> {
-        let ehandler = EngineHandler::new(context.io());
+        checksum: Option<Checksum>,
+    ) -> Result<(), InstallError> {
+        let name = Self::get_file_name(&urls[0]);
+        if checksum.is_none() {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "No checksum available for file '{}', skipping verification.",
+                    name
+                )
+                .color("yellow")
+            )?;
+        }
 
-        match Downloader::download_file_at_url(context.download(), url).await {
-            Ok(c) => {
-                let path =
-                    ehandler.install_from_official_zip(c, version.clone(), system.clone())?;
-                write_stdout!(
-                    context.io(),
-                    "{}\n",
-                    format!(
-                        "Version '{}' installed for system '{}' at path '{}'",
-                        version,
-                        system,
-                        path.display()
-                    )
-                    .color("green")
-                )?;
-            }
-            Err(DownloadError::NotFound(u)) => {
-                write_stdout!(
-                    context.io(),
-                    "{}\n",
-                    format!(
-                        "Version '{}' does not exist for system '{}' (or wrong url: {})",
-                        version, system, u
-                    )
-                    .color("red")
-                )?;
-            }
-            Err(e) => write_stdout!(
+        let target = UserDir::new(context.io()).get_download_path(&name)?;
+        // Other gdpm processes may download the same archive, keep it until it is installed
+        let _lock = Self::lock_download(context.io(), &target).await?;
+        let p = Downloader::download_file_from_urls(
+            context.download(),
+            urls,
+            &target,
+            checksum.as_ref(),
+        )
+        .await
+        .map_err(|e| {
+            InstallError::from_download_error(
+                e,
+                &name,
+                &format!(
+                    "Version '{}' does not exist for system '{}'",
+                    version, system
+                ),
+            )
+        })?;
+
+        // Extraction is blocking, keep it off the runtime so that other downloads progress
+        let io = context.io().clone();
+        let (v, s) = (version.clone(), system.clone());
+        let path = tokio::task::spawn_blocking(move || {
+            EngineHandler::new(&io).install_from_official_zip(&p, v, s)
+        })
+        .await??;
+        write_stdout!(
+            context.io(),
+            "{}\n",
+            format!(
+                "Version '{}' installed for system '{}' at path '{}'",
+                version,
+                system,
+                path.display()
+            )
+            .color("green")
+        )?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn download_and_install_export_templates<
+        I: IoAdapter + Clone + Send + 'static,
+        D: DownloadAdapter,
+    >(
+        context: &Context<I, D>,
+        urls: &[String],
+        version: GodotVersion,
+        checksum: Option<Checksum>,
+    ) -> Result<PathBuf, InstallError> {
+        let name = Self::get_file_name(&urls[0]);
+        if checksum.is_none() {
+            write_stderr!(
                 context.io(),
                 "{}\n",
                 format!(
-                    "Unexpected error while trying to download file at url '{}'\n    | {}",
-                    url, e
+                    "No checksum available for file '{}', skipping verification.",
+                    name
                 )
-                .color("red")
-            )?,
+                .color("yellow")
+            )?;
         }
 
+        let target = UserDir::new(context.io()).get_download_path(&name)?;
+        // Other gdpm processes may download the same archive, keep it until it is installed
+        let _lock = Self::lock_download(context.io(), &target).await?;
+        let p = Downloader::download_file_from_urls(
+            context.download(),
+            urls,
+            &target,
+            checksum.as_ref(),
+        )
+        .await
+        .map_err(|e| {
+            InstallError::from_download_error(
+                e,
+                &name,
+                &format!("Export templates for version '{}' does not exist", version),
+            )
+        })?;
+
+        let io = context.io().clone();
+        let v = version.clone();
+        let path = tokio::task::spawn_blocking(move || {
+            EngineHandler::new(&io).install_export_templates(&p, v)
+        })
+        .await??;
+        write_stdout!(
+            context.io(),
+            "{}\n",
+            format!(
+                "Export templates for version '{}' installed at path '{}'",
+                version,
+                path.display()
+            )
+            .color("green")
+        )?;
+
+        Ok(path)
+    }
+
+    fn get_file_name(url: &str) -> String {
+        url.rsplit('/')
+            .find(|s| !s.is_empty())
+            .unwrap_or("download")
+            .to_string()
+    }
+
+    /// Print an installation error.
+    pub(crate) fn print_install_error<I: IoAdapter, D: DownloadAdapter>(
+        context: &Context<I, D>,
+        error: &InstallError,
+    ) -> Result<(), gdpm_core::io::Error> {
+        write_stderr!(context.io(), "{}\n", error.to_string().color("red"))?;
         Ok(())
     }
 
-    pub(crate) async fn download_and_install_export_templates<I: IoAdapter, D: DownloadAdapter>(
+    /// Download official checksums.
+    ///
+    /// Returns `None` if verification is skipped.
+    pub(crate) async fn download_official_checksums<
+        I: IoAdapter + Clone + Send + 'static,
+        D: DownloadAdapter,
+    >(
         context: &Context<I, D>,
-        url: &str,
         version: GodotVersion,
-    ) -> Result<()> {
-        let ehandler = EngineHandler::new(context.io());
+        mirror_urls: &[String],
+        skip_checksum: bool,
+    ) -> Result<Option<Checksums>, InstallError> {
+        if skip_checksum {
+            return Ok(None);
+        }
 
-        match Downloader::download_file_at_url(context.download(), url).await {
-            Ok(c) => {
-                let path = ehandler.install_export_templates(c, version.clone())?;
-                write_stdout!(
-                    context.io(),
-                    "{}\n",
-                    format!(
-                        "Export templates for version '{}' installed at path '{}'",
-                        version,
-                        path.display()
-                    )
-                    .color("green")
-                )?;
+        let target = UserDir::new(context.io())
+            .get_download_path(&format!("{}-{}", version, OFFICIAL_CHECKSUMS_FILENAME))?;
+        let _lock = Self::lock_download(context.io(), &target).await?;
+
+        Downloader::download_official_checksums(context.download(), version, mirror_urls, &target)
+            .await
+            .map(Some)
+            .map_err(InstallError::MissingOfficialChecksums)
+    }
+
+    /// Get the official checksum of a file.
+    ///
+    /// Returns `None` if verification is skipped.
+    pub(crate) fn get_official_checksum(
+        checksums: Option<&Checksums>,
+        name: &str,
+    ) -> Result<Option<Checksum>, InstallError> {
+        let Some(checksums) = checksums else {
+            return Ok(None);
+        };
+
+        checksums
+            .get(name)
+            .map(Some)
+            .ok_or_else(|| InstallError::MissingOfficialChecksum(name.to_string()))
+    }
+
+    async fn download_and_install_official<
+        I: IoAdapter + Clone + Send + 'static,
+        D: DownloadAdapter,
+    >(
+        context: &Context<I, D>,
+        mirror_urls: &[String],
+        version: GodotVersion,
+        system: SystemVersion,
+        skip_checksum: bool,
+    ) -> Result<(), InstallError> {
+        let editor_urls = mirror_urls
+            .iter()
+            .map(|m| {
+                Downloader::get_official_editor_url_for_version(version.clone(), system.clone(), m)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let templates_urls: Vec<_> = mirror_urls
+            .iter()
+            .map(|m| Downloader::get_official_export_templates_url_for_version(version.clone(), m))
+            .collect();
+
+        let checksums =
+            Self::download_official_checksums(context, version.clone(), mirror_urls, skip_checksum)
+                .await?;
+        let editor_checksum = Self::get_official_checksum(
+            checksums.as_ref(),
+            &Downloader::get_official_editor_filename(&version, system.clone())?,
+        )?;
+        let templates_checksum = Self::get_official_checksum(
+            checksums.as_ref(),
+            &Downloader::get_official_export_templates_filename(&version),
+        )?;
+
+        let (editor, templates) = tokio::join!(
+            Self::download_file_from_urls(
+                context,
+                &editor_urls,
+                version.clone(),
+                system,
+                editor_checksum,
+            ),
+            Self::download_and_install_export_templates(
+                context,
+                &templates_urls,
+                version,
+                templates_checksum,
+            )
+        );
+
+        match (editor, templates) {
+            (Err(e), Err(t)) => {
+                // Only one error can be returned, report the other one here
+                Self::print_install_error(context, &t)?;
+                Err(e)
             }
-            Err(DownloadError::NotFound(u)) => {
-                write_stdout!(
-                    context.io(),
-                    "{}\n",
-                    format!(
-                        "Export templates for version '{}' does not exist (or wrong url: {})",
-                        version, u
-                    )
-                    .color("red")
-                )?;
+            (editor, templates) => editor.and(templates.map(|_| ())),
+        }
+    }
+
+    fn resolve_remote_version<I: IoAdapter, D: DownloadAdapter>(
+        context: &Context<I, D>,
+        req: &GodotVersionReq,
+        remote_versions: &[GodotVersion],
+    ) -> Result<GodotVersion> {
+        // Remote releases are listed without their mono variant
+        let candidates: Vec<_> = remote_versions
+            .iter()
+            .map(|v| GodotVersion::new(v.version(), v.kind().clone(), req.mono()))
+            .collect();
+
+        match req.resolve(&candidates) {
+            Some(v) => {
+                info!(
+                    "Resolved '{}' to engine version '{}'.",
+                    req,
+                    v.to_string().color("green")
+                );
+                Ok(v)
             }
-            Err(e) => {
-                write_stdout!(
+            None => {
+                write_stderr!(
                     context.io(),
                     "{}\n",
-                    format!(
-                        "Unexpected error while trying to download file at url '{}'\n    | {}",
-                        url, e
-                    )
-                    .color("red")
+                    format!("No remote engine version matches '{}'.", req).color("red")
                 )?;
+                std::process::exit(1);
             }
         }
-
-        Ok(())
     }
 
-    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+    pub fn execute<I: IoAdapter + Clone + Send + 'static, D: DownloadAdapter>(
+        self,
+        context: &Context<I, D>,
+    ) -> Result<()> {
         let ehandler = EngineHandler::new(context.io());
-        let (version, system) =
-            parse_godot_version_args(context, &self.engine, self.headless, self.server)?;
 
-        let existing_version = ehandler.has_version(&version)?;
-        if existing_version.is_some() {
-            if !self.overwrite {
-                write_stderr!(
-                    context.io(),
-                    "{}\n",
-                    format!("Engine version '{}' is already installed. Use '--overwrite' to force installation.", version).color("yellow")
-                )?;
-                std::process::exit(1);
-            } else {
-                info!(
-                    "Will overwrite existing engine version '{}'.",
-                    version.to_string().color("green")
-                );
+        if self.engine.len() > 1 && (self.target_path.is_some() || self.target_url.is_some()) {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                "Only one engine version can be added from a target path or URL.".color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        let requirements = self
+            .engine
+            .iter()
+            .map(|e| expand_alias_or_exit(context, e))
+            .collect::<Result<Vec<_>>>()?;
+        let has_requirements = requirements.iter().any(|e| e.exact().is_none());
+        if has_requirements && (self.target_path.is_some() || self.target_url.is_some()) {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                "An exact engine version is required with a target path or URL.".color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        let remote_versions = if has_requirements {
+            rt.block_on(ListRemote::lookup_remote_versions(context, self.refresh))?
+        } else {
+            vec![]
+        };
+
+        let mut versions = vec![];
+        for req in &requirements {
+            let engine = match req.exact() {
+                Some(v) => v.clone(),
+                None => Self::resolve_remote_version(
+                    context,
+                    &req.clone().with_pre_release(self.pre_release),
+                    &remote_versions,
+                )?,
+            };
+            let (version, system) =
+                parse_godot_version_args(context, &engine, self.headless, self.server)?;
+
+            // Downloaded archives are official builds
+            if self.target_path.is_none() {
+                if let Err(e) = system.get_archive_basename(&version) {
+                    write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+                    std::process::exit(1);
+                }
             }
+
+            let existing_version = ehandler.has_version(&version)?;
+            if existing_version.is_some() {
+                if !self.overwrite {
+                    write_stderr!(
+                        context.io(),
+                        "{}\n",
+                        format!("Engine version '{}' is already installed. Use '--overwrite' to force installation.", version).color("yellow")
+                    )?;
+                    std::process::exit(1);
+                } else {
+                    info!(
+                        "Will overwrite existing engine version '{}'.",
+                        version.to_string().color("green")
+                    );
+                }
+            }
+
+            versions.push((version, system));
         }
 
         if let Some(path) = self.target_path {
-            let engine_info = EngineInfo::new(context.io(), self.engine, path)?;
+            let (version, _) = versions.remove(0);
+            let mut engine_info = EngineInfo::new(context.io(), version, path)?;
+            match ehandler.update_build_info(&mut engine_info) {
+                Ok(Some(build)) => print_build_mismatch_warning(context, &engine_info, &build)?,
+                Ok(None) => (),
+                Err(e) => write_stderr!(
+                    context.io(),
+                    "{}\n",
+                    format!(
+                        "Could not check engine version, registering it as '{}'.\n    | {}",
+                        engine_info.version, e
+                    )
+                    .color("yellow")
+                )?,
+            }
+
             let verbose_name = engine_info.get_verbose_name();
-            let ehandler = EngineHandler::new(context.io());
             ehandler.register(engine_info)?;
 
             write_stdout!(context.io(), "{} is registered.\n", verbose_name)?;
@@ -169,42 +508,61 @@ impl Add {
         }
 
         if let Some(url) = self.target_url {
-            let rt = tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-            rt.block_on(Self::download_file_at_url(context, &url, version, system))?;
+            let (version, system) = versions.remove(0);
+            let checksum = self
+                .sha256
+                .map(Checksum::Sha256)
+                .or(self.sha512.map(Checksum::Sha512));
+
+            if let Err(e) = rt.block_on(Self::download_file_from_urls(
+                context,
+                &[url],
+                version,
+                system,
+                checksum,
+            )) {
+                Self::print_install_error(context, &e)?;
+                std::process::exit(1);
+            }
 
             write_stderr!(
                 context.io(),
-                "Cannot fetch export templates, missing URL.\n"
+                "Cannot fetch export templates, missing URL. Use `engine templates add` to install them.\n"
             )?;
         } else {
-            let editor_url = Downloader::get_official_editor_url_for_version(
-                version.clone(),
-                system.clone(),
-                MIRROR_URL,
-            );
-            let templates_url = Downloader::get_official_export_templates_url_for_version(
-                version.clone(),
-                MIRROR_URL,
-            );
-
-            let rt = tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-            rt.block_on(Self::download_file_at_url(
-                context,
-                &editor_url,
-                version.clone(),
-                system,
-            ))?;
-            rt.block_on(Self::download_and_install_export_templates(
-                context,
-                &templates_url,
-                version,
-            ))?;
+            let gconf = GlobalConfig::new(context.io());
+            let mut mirror_urls = self.mirror;
+            for mirror in gconf.get_mirrors()? {
+                if !mirror_urls.contains(&mirror) {
+                    mirror_urls.push(mirror);
+                }
+            }
+
+            // Download all versions concurrently
+            let results = rt.block_on(join_all(versions.into_iter().map(|(version, system)| {
+                Self::download_and_install_official(
+                    context,
+                    &mirror_urls,
+                    version,
+                    system,
+                    self.skip_checksum,
+                )
+            })));
+            let mut failed = 0;
+            for error in results.into_iter().filter_map(|r| r.err()) {
+                Self::print_install_error(context, &error)?;
+                failed += 1;
+            }
+
+            // Every installation is finished at this point, nothing is left half-done
+            if failed > 0 {
+                write_stderr!(
+                    context.io(),
+                    "{}\n",
+                    format!("{} engine version(s) could not be installed.", failed).color("red")
+                )?;
+                std::process::exit(1);
+            }
         }
 
         Ok(())
@@ -0,0 +1,115 @@
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    config::GlobalConfig,
+    downloader::DownloadAdapter,
+    io::{write_stderr, write_stdout, IoAdapter},
+    types::version::{is_alias_name, GodotVersionReq},
+};
+
+use crate::context::Context;
+
+/// Show, set or unset engine aliases
+#[derive(Parser)]
+pub struct Alias {
+    /// Alias name (e.g. lts)
+    name: Option<String>,
+
+    /// Target engine version or requirement (e.g. 4.2.2, 4.2, stable.mono)
+    #[clap(conflicts_with = "unset")]
+    target: Option<GodotVersionReq>,
+
+    /// Unset alias
+    #[clap(long, requires = "name")]
+    unset: bool,
+}
+
+impl Alias {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let gconf = GlobalConfig::new(context.io());
+
+        let Some(name) = self.name else {
+            let aliases = gconf.get_aliases()?;
+            if aliases.is_empty() {
+                write_stdout!(context.io(), "{}", "No alias defined.\n".color("yellow"))?;
+            } else {
+                for (name, target) in aliases {
+                    write_stdout!(
+                        context.io(),
+                        "- {} = {}\n",
+                        name.color("green"),
+                        target.to_string().color("green")
+                    )?;
+                }
+            }
+            return Ok(());
+        };
+
+        if self.unset {
+            if gconf.unset_alias(&name)? {
+                write_stdout!(context.io(), "Alias '{}' removed.\n", name.color("green"))?;
+            } else {
+                write_stdout!(
+                    context.io(),
+                    "{}",
+                    format!("Alias '{}' is not defined.\n", name).color("yellow")
+                )?;
+            }
+            return Ok(());
+        }
+
+        let Some(target) = self.target else {
+            match gconf.get_alias(&name)? {
+                Some(target) => {
+                    write_stdout!(context.io(), "{}\n", target.to_string().color("green"))?
+                }
+                None => {
+                    write_stderr!(
+                        context.io(),
+                        "{}\n",
+                        format!("Alias '{}' is not defined.", name).color("red")
+                    )?;
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        };
+
+        if !is_alias_name(&name) {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Invalid alias name '{}': it should start with a letter, only contain letters, digits, '-' or '_', and not be a channel name.",
+                    name
+                )
+                .color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        if target.alias().is_some() {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Alias '{}' can not target another alias ('{}').",
+                    name, target
+                )
+                .color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        gconf.set_alias(&name, &target)?;
+        write_stdout!(
+            context.io(),
+            "Alias '{}' set to '{}'.\n",
+            name.color("green"),
+            target.to_string().color("green")
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    build::{
+        get_build_kind, get_default_build_name, BuildHandler, BuildOptions, DEFAULT_REPOSITORY,
+    },
+    downloader::DownloadAdapter,
+    engine::EngineHandler,
+    error::BuildError,
+    io::{write_stderr, write_stdout, IoAdapter},
+};
+
+use crate::context::Context;
+
+/// Build engine from source with SCons, and register it as a custom version
+#[derive(Parser)]
+pub struct Build {
+    /// Git reference to build (branch, tag or commit)
+    git_ref: String,
+    /// Custom build name (e.g. custom.mybranch), defaults to the git reference
+    #[clap(long)]
+    name: Option<String>,
+    /// Git repository URL, to build a fork
+    #[clap(long, default_value = DEFAULT_REPOSITORY)]
+    repository: String,
+    /// Additional SCons arguments (e.g. production=yes module_mono_enabled=yes)
+    #[clap(long, num_args = 1..)]
+    scons_args: Vec<String>,
+    /// Also build export templates
+    #[clap(long)]
+    templates: bool,
+    /// Allow overwrite
+    #[clap(long)]
+    overwrite: bool,
+}
+
+impl Build {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let bhandler = BuildHandler::new(context.io());
+        let ehandler = EngineHandler::new(context.io());
+        let name = self
+            .name
+            .unwrap_or_else(|| get_default_build_name(&self.git_ref));
+
+        let result = get_build_kind(&name).and_then(|kind| {
+            let source_dir = bhandler.checkout(&self.repository, &self.git_ref)?;
+            let version = bhandler.get_build_version(&source_dir, kind)?;
+            Ok((source_dir, version))
+        });
+        let (source_dir, version) = match result {
+            Ok(r) => r,
+            Err(e @ (BuildError::InvalidName(_) | BuildError::IoError(_))) => {
+                write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+                std::process::exit(1);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if ehandler.has_version(&version)?.is_some() && !self.overwrite {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!("Engine version '{}' is already installed. Use '--overwrite' to force installation.", version).color("yellow")
+            )?;
+            std::process::exit(1);
+        }
+
+        let options = BuildOptions {
+            scons_args: self.scons_args,
+            templates: self.templates,
+        };
+        match bhandler.build(&source_dir, &version, &options) {
+            Ok(output) => {
+                write_stdout!(
+                    context.io(),
+                    "{}\n",
+                    format!(
+                        "Version '{}' built and registered at path '{}'",
+                        version,
+                        output.engine.path.display()
+                    )
+                    .color("green")
+                )?;
+                if let Some(path) = output.templates_path {
+                    write_stdout!(
+                        context.io(),
+                        "{}\n",
+                        format!(
+                            "Export templates for version '{}' installed at path '{}'",
+                            version,
+                            path.display()
+                        )
+                        .color("green")
+                    )?;
+                }
+            }
+            Err(e @ (BuildError::MissingEditorBinary(_) | BuildError::IoError(_))) => {
+                write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+                std::process::exit(1);
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(())
+    }
+}
@@ -5,44 +5,61 @@ use gdpm_core::{
     downloader::DownloadAdapter,
     engine::EngineHandler,
     io::{write_stdout, IoAdapter},
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
 use crate::{
-    common::{print_missing_default_engine_message, validate_engine_version_or_exit},
+    common::{
+        get_exact_version_or_exit, print_missing_default_engine_message,
+        resolve_engine_version_or_exit, validate_engine_version_or_exit,
+    },
     context::Context,
 };
 
 /// Show or set default engine
 #[derive(Parser)]
 pub struct Default {
-    /// Engine verion
-    engine: Option<GodotVersion>,
+    /// Engine version or alias (aliases are stored as-is, so that updating them changes the default engine)
+    engine: Option<GodotVersionReq>,
 }
 
 impl Default {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
-        if let Some(version) = self.engine {
-            validate_engine_version_or_exit(context, &version)?;
-            let ehandler = EngineHandler::new(context.io());
-            ehandler.set_as_default(&version)?;
-            write_stdout!(
-                context.io(),
-                "Godot Engine v{} set as default.\n",
-                version.to_string().color("green")
-            )?;
-        } else {
-            let ehandler = EngineHandler::new(context.io());
-            if let Some(e) = ehandler.get_default()? {
+        let ehandler = EngineHandler::new(context.io());
+
+        if let Some(req) = self.engine {
+            if req.alias().is_some() {
+                let engine = resolve_engine_version_or_exit(context, &req)?;
+                ehandler.set_default_alias(&req)?;
                 write_stdout!(
                     context.io(),
-                    "{} {}\n",
-                    "*".color("green"),
-                    e.to_string().color("green")
+                    "Engine alias '{}' (Godot Engine v{}) set as default.\n",
+                    req.to_string().color("green"),
+                    engine.version.to_string().color("green")
                 )?;
             } else {
-                print_missing_default_engine_message(context)?;
+                let version = get_exact_version_or_exit(context, &req)?;
+                validate_engine_version_or_exit(context, &version)?;
+                ehandler.set_as_default(&version)?;
+                write_stdout!(
+                    context.io(),
+                    "Godot Engine v{} set as default.\n",
+                    version.to_string().color("green")
+                )?;
+            }
+        } else if let Some(e) = ehandler.get_default()? {
+            write_stdout!(
+                context.io(),
+                "{} {}",
+                "*".color("green"),
+                e.to_string().color("green")
+            )?;
+            if let Some(alias) = ehandler.get_default_alias()? {
+                write_stdout!(context.io(), " (alias '{}')", alias)?;
             }
+            write_stdout!(context.io(), "\n")?;
+        } else {
+            print_missing_default_engine_message(context)?;
         }
 
         Ok(())
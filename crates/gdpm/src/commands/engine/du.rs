@@ -0,0 +1,59 @@
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    engine::EngineHandler,
+    io::{write_stdout, IoAdapter},
+};
+
+use crate::{common::format_size, context::Context};
+
+/// Show disk usage of engines and their export templates
+#[derive(Parser)]
+pub struct Du;
+
+impl Du {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let usage = ehandler.disk_usage()?;
+
+        if usage.is_empty() {
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                "No engine registered. Use `engine register` to register an engine."
+                    .color("yellow")
+            )?;
+            return Ok(());
+        }
+
+        for entry in &usage {
+            let engine_size = match entry.engine_size {
+                Some(s) => format_size(s),
+                None => "external".to_string(),
+            };
+            let templates_size = match entry.templates_size {
+                Some(s) => format_size(s),
+                None => "-".to_string(),
+            };
+
+            write_stdout!(
+                context.io(),
+                "{} - engine: {}, templates: {}\n",
+                entry.engine.get_verbose_name(),
+                engine_size,
+                templates_size
+            )?;
+        }
+
+        let total: u64 = usage.iter().map(|e| e.total()).sum();
+        write_stdout!(
+            context.io(),
+            "{}\n",
+            format!("Total: {}", format_size(total)).color("green")
+        )?;
+
+        Ok(())
+    }
+}
@@ -4,10 +4,12 @@ use colored::Colorize;
 use gdpm_core::{
     downloader::DownloadAdapter,
     engine::EngineHandler,
-    io::{write_stdout, IoAdapter},
+    io::{write_stderr, write_stdout, IoAdapter},
 };
 
-use crate::context::Context;
+use tracing::debug;
+
+use crate::{common::print_build_mismatch_warning, context::Context};
 
 /// List engines
 #[derive(Parser)]
@@ -28,7 +30,7 @@ impl List {
                     .color("yellow")
             )?;
         } else {
-            for entry in entries {
+            for entry in &entries {
                 if let Some(default) = &default_entry {
                     if entry.has_same_slug(default) {
                         write_stdout!(context.io(), "{} ", "*".color("green"))?;
@@ -39,10 +41,35 @@ impl List {
                     write_stdout!(context.io(), "  ")?;
                 }
 
-                write_stdout!(context.io(), "{}\n", entry.get_verbose_name())?;
+                write_stdout!(context.io(), "{}", entry.get_verbose_name())?;
+                if let Some(hash) = &entry.hash {
+                    write_stdout!(context.io(), " [{}]", hash)?;
+                }
+                write_stdout!(context.io(), "\n")?;
+            }
+        }
+
+        for entry in &entries {
+            match ehandler.check_build(entry) {
+                Ok(Some(build)) => print_build_mismatch_warning(context, entry, &build)?,
+                Ok(None) => (),
+                Err(e) => debug!("Could not check engine '{}': {}", entry.version, e),
             }
         }
 
+        let issues = ehandler.check()?;
+        if !issues.is_empty() {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "{} engine configuration issue(s) found, use `gdpm doctor` for details.",
+                    issues.len()
+                )
+                .color("yellow")
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -1,37 +1,113 @@
 use clap::Parser;
 use color_eyre::Result;
+use colored::Colorize;
 use gdpm_core::{
+    config::GlobalConfig,
     downloader::DownloadAdapter,
+    engine::EngineHandler,
     io::{write_stdout, IoAdapter},
+    types::version::GodotVersion,
 };
+use tracing::debug;
 
 use crate::context::Context;
 
 /// List engines from remote
 #[derive(Parser)]
 #[clap(name = "list-remote", alias = "ls-remote")]
-pub struct ListRemote;
+pub struct ListRemote {
+    /// Only list stable versions
+    #[clap(long)]
+    stable_only: bool,
+    /// Only list versions starting from this version (e.g. 4.0)
+    #[clap(long)]
+    since: Option<GodotVersion>,
+    /// List mono editions
+    #[clap(long)]
+    mono: bool,
+    /// Only list installed versions
+    #[clap(long)]
+    installed: bool,
+    /// Output as JSON
+    #[clap(long)]
+    json: bool,
+    /// Ignore cached versions and fetch them again
+    #[clap(long)]
+    refresh: bool,
+}
 
 impl ListRemote {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
         let rt = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
-            .build()
-            .unwrap();
-        rt.block_on(Self::lookup_remote_versions(context))?;
+            .build()?;
+        let versions = rt.block_on(Self::lookup_remote_versions(context, self.refresh))?;
+
+        let ehandler = EngineHandler::new(context.io());
+        let installed: Vec<_> = ehandler.list()?.into_iter().map(|e| e.version).collect();
+
+        let entries: Vec<_> = versions
+            .into_iter()
+            .map(|v| GodotVersion::new(v.version(), v.kind().clone(), self.mono))
+            .filter(|v| !self.stable_only || v.is_stable())
+            .filter(|v| self.since.as_ref().map(|s| v >= s).unwrap_or(true))
+            .map(|v| {
+                let is_installed = installed.contains(&v);
+                (v, is_installed)
+            })
+            .filter(|(_, is_installed)| !self.installed || *is_installed)
+            .collect();
+
+        if self.json {
+            let entries: Vec<_> = entries
+                .into_iter()
+                .map(|(v, is_installed)| {
+                    serde_json::json!({
+                        "name": v.to_string(),
+                        "version": v.version(),
+                        "kind": v.kind().to_string(),
+                        "mono": v.mono(),
+                        "installed": is_installed,
+                    })
+                })
+                .collect();
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                serde_json::to_string_pretty(&entries)?
+            )?;
+        } else {
+            for (version, is_installed) in entries {
+                if is_installed {
+                    write_stdout!(context.io(), "{} {}\n", "*".color("green"), version)?;
+                } else {
+                    write_stdout!(context.io(), "  {}\n", version)?;
+                }
+            }
+        }
 
         Ok(())
     }
 
+    /// Lookup remote versions, using the cache unless it has expired or `refresh` is set.
     pub(crate) async fn lookup_remote_versions<I: IoAdapter, D: DownloadAdapter>(
         context: &Context<I, D>,
-    ) -> Result<()> {
-        let versions = context.download().lookup_remote_versions().await?;
-
-        for version in versions {
-            write_stdout!(context.io(), "{}\n", version)?;
+        refresh: bool,
+    ) -> Result<Vec<GodotVersion>> {
+        let ehandler = EngineHandler::new(context.io());
+        if !refresh {
+            let ttl = GlobalConfig::new(context.io())
+                .load_download_config()?
+                .remote_cache_ttl;
+            if let Some(versions) = ehandler.read_versions_from_cache(ttl)? {
+                debug!("Using cached remote versions.");
+                return Ok(versions);
+            }
         }
 
-        Ok(())
+        let versions = context.download().lookup_remote_versions().await?;
+        ehandler.write_versions_in_cache(&versions)?;
+
+        Ok(versions)
     }
 }
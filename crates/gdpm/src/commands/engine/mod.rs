@@ -1,6 +1,12 @@
 pub mod add;
+pub mod alias;
+pub mod build;
 pub mod default;
+pub mod du;
 pub mod list;
 pub mod list_remote;
+pub mod prune;
 pub mod remove;
 pub mod run;
+pub mod scan;
+pub mod templates;
@@ -0,0 +1,149 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    config::GlobalConfig,
+    downloader::DownloadAdapter,
+    engine::EngineHandler,
+    io::{write_stderr, write_stdout, IoAdapter},
+    pin::PinHandler,
+    project::ProjectHandler,
+};
+use tracing::debug;
+
+use crate::{common::format_size, context::Context};
+
+/// Remove installed engines which are not used anymore
+#[derive(Parser)]
+pub struct Prune {
+    /// Folder containing projects, in addition to the `projects/roots` configuration
+    #[clap(long = "root")]
+    roots: Vec<PathBuf>,
+    /// Remove engines not used for this number of days
+    #[clap(long)]
+    unused_days: Option<u64>,
+    /// Maximum folder depth when searching projects
+    #[clap(long, default_value_t = 4)]
+    depth: usize,
+    /// Only show engines which would be removed
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Prune {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let gconf = GlobalConfig::new(context.io());
+
+        let mut roots = self.roots;
+        roots.extend(gconf.get_project_roots()?);
+
+        if roots.is_empty() && self.unused_days.is_none() {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                "No project root configured, use `--root` or `--unused-days`.".color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        let referenced = if roots.is_empty() {
+            None
+        } else {
+            let phandler = ProjectHandler::new(context.io());
+            let pinhandler = PinHandler::new(context.io());
+            let mut referenced = vec![];
+            let mut unresolved = 0;
+
+            for root in &roots {
+                for project in phandler.find_projects(root, self.depth)? {
+                    let resolved = match pinhandler.get_project_pinned_version(&project) {
+                        Ok(Some((req, _source))) => ehandler.resolve(&req),
+                        Ok(None) => continue,
+                        Err(e) => Err(e),
+                    };
+
+                    match resolved {
+                        Ok(engine) => {
+                            debug!(
+                                "Project '{}' uses engine '{}'",
+                                project.display(),
+                                engine.version
+                            );
+                            referenced.push(engine.version);
+                        }
+                        Err(e) => {
+                            unresolved += 1;
+                            write_stderr!(
+                                context.io(),
+                                "{}\n",
+                                format!(
+                                    "Could not resolve the engine of project '{}'.\n    | {}",
+                                    project.display(),
+                                    e
+                                )
+                                .color("yellow")
+                            )?;
+                        }
+                    }
+                }
+            }
+
+            // Engines of unresolved projects can not be told apart, keep them all
+            if unresolved > 0 {
+                write_stderr!(
+                    context.io(),
+                    "{}\n",
+                    format!(
+                        "{} project(s) could not be resolved, nothing was removed. Fix their engine pin or remove them from the project roots.",
+                        unresolved
+                    )
+                    .color("red")
+                )?;
+                std::process::exit(1);
+            }
+
+            Some(referenced)
+        };
+
+        let unused_for = self
+            .unused_days
+            .map(|d| Duration::from_secs(d * 24 * 60 * 60));
+        let candidates = ehandler.get_prune_candidates(referenced.as_deref(), unused_for)?;
+
+        if candidates.is_empty() {
+            write_stdout!(context.io(), "{}\n", "Nothing to prune.".color("green"))?;
+            return Ok(());
+        }
+
+        let usage = ehandler.disk_usage()?;
+        let mut freed = 0;
+        for engine in &candidates {
+            freed += usage
+                .iter()
+                .find(|u| u.engine.version == engine.version)
+                .map(|u| u.total())
+                .unwrap_or(0);
+
+            if self.dry_run {
+                write_stdout!(context.io(), "Would remove {}\n", engine.get_verbose_name())?;
+            } else {
+                ehandler.uninstall(&engine.version)?;
+                write_stdout!(context.io(), "Removed {}\n", engine.get_verbose_name())?;
+            }
+        }
+
+        let summary = format!(
+            "{} engine(s) {}, {} {}.",
+            candidates.len(),
+            if self.dry_run { "to remove" } else { "removed" },
+            format_size(freed),
+            if self.dry_run { "to free" } else { "freed" },
+        );
+        write_stdout!(context.io(), "{}\n", summary.color("green"))?;
+
+        Ok(())
+    }
+}
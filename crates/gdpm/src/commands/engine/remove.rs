@@ -6,17 +6,20 @@ use gdpm_core::{
     engine::EngineHandler,
     error::EngineError,
     io::{write_stdout, IoAdapter},
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
-use crate::{common::parse_godot_version_args, context::Context};
+use crate::{
+    common::{get_exact_version_or_exit, parse_godot_version_args},
+    context::Context,
+};
 
 /// Uninstall engine
 #[derive(Parser)]
 #[clap(name = "remove", alias = "rm")]
 pub struct Remove {
-    /// Engine version
-    engine: GodotVersion,
+    /// Engine version or alias
+    engine: GodotVersionReq,
     /// Headless?
     #[clap(long)]
     headless: bool,
@@ -27,8 +30,9 @@ pub struct Remove {
 
 impl Remove {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let engine = get_exact_version_or_exit(context, &self.engine)?;
         let (version, _system) =
-            parse_godot_version_args(context, &self.engine, self.headless, self.server)?;
+            parse_godot_version_args(context, &engine, self.headless, self.server)?;
 
         let ehandler = EngineHandler::new(context.io());
         match ehandler.uninstall(&version) {
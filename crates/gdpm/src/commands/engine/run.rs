@@ -7,20 +7,23 @@ use gdpm_core::{
     downloader::DownloadAdapter,
     engine::EngineHandler,
     io::{write_stdout, IoAdapter},
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
 use crate::{
-    common::{print_missing_default_engine_message, validate_engine_version_or_exit},
+    common::{get_engine_for_path_or_exit, print_missing_default_engine_message},
     context::Context,
 };
 
 /// Run command on engine
 #[derive(Parser)]
 pub struct Run {
-    /// Engine version
+    /// Engine version or requirement (e.g. 4.3, 4.x, ^4.2, latest)
     #[clap(short, long)]
-    engine: Option<GodotVersion>,
+    engine: Option<GodotVersionReq>,
+    /// Allow pre-release versions when resolving the engine requirement
+    #[clap(long)]
+    pre_release: bool,
     /// Arguments
     args: Vec<String>,
 }
@@ -28,9 +31,9 @@ pub struct Run {
 impl Run {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
         let ehandler = EngineHandler::new(context.io());
-        if let Some(v) = self.engine {
-            validate_engine_version_or_exit(context, &v)?;
-
+        let engine = self.engine.map(|e| e.with_pre_release(self.pre_release));
+        if let Some(e) = get_engine_for_path_or_exit(context, engine, Path::new("."))? {
+            let v = e.version;
             if self.args.is_empty() {
                 write_stdout!(
                     context.io(),
@@ -47,23 +50,6 @@ impl Run {
                 )?;
                 ehandler.exec_version_for_project(&v, &self.args, Path::new("."))?;
             }
-        } else if let Some(e) = ehandler.get_default()? {
-            if self.args.is_empty() {
-                write_stdout!(
-                    context.io(),
-                    "Executing command {} on Godot Engine v{} ...\n",
-                    self.args.join(" ").color("blue"),
-                    e.to_string().color("green")
-                )?;
-                ehandler.exec_version_for_project(&e, &self.args, Path::new("."))?;
-            } else {
-                write_stdout!(
-                    context.io(),
-                    "Running Godot Engine v{} ...\n",
-                    e.to_string().color("green")
-                )?;
-                ehandler.run_version_for_project(&e, Path::new("."))?;
-            }
         } else {
             print_missing_default_engine_message(context)?;
         }
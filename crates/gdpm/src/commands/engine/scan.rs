@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use crate::context::Context;
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    engine::{EngineHandler, ScannedEngine},
+    io::{write_stdout, IoAdapter},
+};
+
+/// Find Godot executables in a folder and register them
+#[derive(Parser)]
+pub struct Scan {
+    /// Folder to scan
+    path: PathBuf,
+    /// Maximum folder depth
+    #[clap(long, default_value_t = 4)]
+    depth: usize,
+    /// Only show found engines, without registering them
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Scan {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let mut registered = 0;
+        let mut duplicates = 0;
+
+        for scanned in ehandler.scan_engines(&self.path, self.depth)? {
+            match scanned {
+                ScannedEngine::New(engine_info) => {
+                    if !self.dry_run {
+                        ehandler.register(engine_info.clone())?;
+                    }
+                    write_stdout!(context.io(), "Found {}\n", engine_info.get_verbose_name())?;
+                    registered += 1;
+                }
+                ScannedEngine::AlreadyRegistered(existing) => write_stdout!(
+                    context.io(),
+                    "Already registered {}\n",
+                    existing.get_verbose_name()
+                )?,
+                ScannedEngine::Duplicate(path, existing) => {
+                    duplicates += 1;
+                    write_stdout!(
+                        context.io(),
+                        "{}\n",
+                        format!(
+                            "Duplicate version '{}' at path '{}' (already registered at path '{}').",
+                            existing.version,
+                            path.display(),
+                            existing.path.display()
+                        )
+                        .color("yellow")
+                    )?;
+                }
+            }
+        }
+
+        let summary = format!(
+            "{} engine(s) {}, {} duplicate(s) skipped.",
+            registered,
+            if self.dry_run { "found" } else { "registered" },
+            duplicates
+        );
+        write_stdout!(context.io(), "{}\n", summary.color("green"))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::Result;
+use colored::Colorize;
+use gdpm_core::{
+    config::{GlobalConfig, GodotDir},
+    downloader::{checksum::Checksum, download::Downloader, error::DownloadError, DownloadAdapter},
+    engine::EngineHandler,
+    error::EngineError,
+    io::{write_stderr, write_stdout, IoAdapter},
+    types::version::GodotVersionReq,
+};
+
+use crate::{
+    commands::engine::add::Add as AddEngine, common::get_exact_version_or_exit, context::Context,
+};
+
+/// Download and install export templates from official mirror or specific URL / path
+#[derive(Parser)]
+pub struct Add {
+    /// Engine version or alias
+    engine: GodotVersionReq,
+    /// Target URL
+    #[clap(long, conflicts_with = "target_path")]
+    target_url: Option<String>,
+    /// Target path of a `.tpz` archive
+    #[clap(long)]
+    target_path: Option<PathBuf>,
+    /// Expected SHA-256 checksum of the archive at target URL
+    #[clap(long, requires = "target_url", conflicts_with = "sha512")]
+    sha256: Option<String>,
+    /// Expected SHA-512 checksum of the archive at target URL
+    #[clap(long, requires = "target_url")]
+    sha512: Option<String>,
+    /// Download mirror URL, tried before configured mirrors (can be repeated)
+    #[clap(long, value_parser = parse_mirror_url, conflicts_with_all = ["target_url", "target_path"])]
+    mirror: Vec<String>,
+    /// Install without verifying official checksums, when they are unavailable (not recommended)
+    #[clap(long, conflicts_with_all = ["target_url", "target_path"])]
+    skip_checksum: bool,
+    /// Allow overwrite
+    #[clap(long)]
+    overwrite: bool,
+}
+
+/// Uninstall export templates
+#[derive(Parser)]
+#[clap(name = "remove", alias = "rm")]
+pub struct Remove {
+    /// Engine version or alias
+    engine: GodotVersionReq,
+}
+
+/// List installed export templates
+#[derive(Parser)]
+#[clap(name = "list", alias = "ls")]
+pub struct List;
+
+/// Check installed export templates
+#[derive(Parser)]
+pub struct Verify {
+    /// Engine version or alias (defaults to all installed export templates)
+    engine: Option<GodotVersionReq>,
+}
+
+fn parse_mirror_url(url: &str) -> Result<String, DownloadError> {
+    Downloader::parse_mirror_url(url)
+}
+
+impl Add {
+    pub fn execute<I: IoAdapter + Clone + Send + 'static, D: DownloadAdapter>(
+        self,
+        context: &Context<I, D>,
+    ) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let gdir = GodotDir::new(context.io());
+        let version = get_exact_version_or_exit(context, &self.engine)?;
+
+        let templates_path = gdir.get_specific_export_templates_directory(&version)?;
+        if context.io().path_exists(&templates_path) && !self.overwrite {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Export templates for version '{}' are already installed. Use '--overwrite' to force installation.",
+                    version
+                )
+                .color("yellow")
+            )?;
+            std::process::exit(1);
+        }
+
+        if let Some(path) = self.target_path {
+            let path = ehandler.extract_export_templates(&path, version.clone())?;
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Export templates for version '{}' installed at path '{}'",
+                    version,
+                    path.display()
+                )
+                .color("green")
+            )?;
+            return Ok(());
+        }
+
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        let result = if let Some(url) = self.target_url {
+            let checksum = self
+                .sha256
+                .map(Checksum::Sha256)
+                .or(self.sha512.map(Checksum::Sha512));
+
+            rt.block_on(AddEngine::download_and_install_export_templates(
+                context,
+                &[url],
+                version,
+                checksum,
+            ))
+        } else {
+            let gconf = GlobalConfig::new(context.io());
+            let mut mirror_urls = self.mirror;
+            for mirror in gconf.get_mirrors()? {
+                if !mirror_urls.contains(&mirror) {
+                    mirror_urls.push(mirror);
+                }
+            }
+
+            let urls: Vec<_> = mirror_urls
+                .iter()
+                .map(|m| {
+                    Downloader::get_official_export_templates_url_for_version(version.clone(), m)
+                })
+                .collect();
+
+            rt.block_on(async {
+                let checksums = AddEngine::download_official_checksums(
+                    context,
+                    version.clone(),
+                    &mirror_urls,
+                    self.skip_checksum,
+                )
+                .await?;
+                let checksum = AddEngine::get_official_checksum(
+                    checksums.as_ref(),
+                    &Downloader::get_official_export_templates_filename(&version),
+                )?;
+
+                AddEngine::download_and_install_export_templates(context, &urls, version, checksum)
+                    .await
+            })
+        };
+
+        if let Err(e) = result {
+            AddEngine::print_install_error(context, &e)?;
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl Remove {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let version = get_exact_version_or_exit(context, &self.engine)?;
+        match ehandler.uninstall_export_templates(&version) {
+            Ok(path) => write_stdout!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Export templates for version '{}' removed from path '{}'.",
+                    version,
+                    path.display()
+                )
+                .color("green")
+            )?,
+            Err(e @ EngineError::ExportTemplatesNotInstalled(_)) => {
+                write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+                std::process::exit(1);
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(())
+    }
+}
+
+impl List {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let templates = ehandler.list_export_templates()?;
+
+        if templates.is_empty() {
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                "No export templates installed. Use `engine templates add` to install them."
+                    .color("yellow")
+            )?;
+        } else {
+            for (version, path) in templates {
+                write_stdout!(context.io(), "{} ({})\n", version, path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Verify {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let ehandler = EngineHandler::new(context.io());
+        let versions = match self.engine {
+            Some(req) => vec![get_exact_version_or_exit(context, &req)?],
+            None => ehandler
+                .list_export_templates()?
+                .into_iter()
+                .map(|(v, _)| v)
+                .collect(),
+        };
+
+        let mut failed = false;
+        for version in versions {
+            match ehandler.verify_export_templates(&version) {
+                Ok(issues) if issues.is_empty() => write_stdout!(
+                    context.io(),
+                    "{}\n",
+                    format!("Export templates for version '{}' are valid.", version).color("green")
+                )?,
+                Ok(issues) => {
+                    failed = true;
+                    write_stdout!(
+                        context.io(),
+                        "{}\n",
+                        format!("Export templates for version '{}' are invalid:", version)
+                            .color("red")
+                    )?;
+                    for issue in issues {
+                        write_stdout!(context.io(), "    | {}\n", issue)?;
+                    }
+                }
+                Err(e @ EngineError::ExportTemplatesNotInstalled(_)) => {
+                    failed = true;
+                    write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if failed {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
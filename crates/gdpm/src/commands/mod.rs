@@ -1,4 +1,6 @@
 pub mod args;
 mod dependencies;
-mod engine;
+mod doctor;
+pub(crate) mod engine;
 mod project;
+mod shim;
@@ -0,0 +1,116 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::Parser;
+use color_eyre::Result;
+
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    io::{write_stderr, write_stdout, IoAdapter},
+    parser::GdValue,
+    project::ProjectHandler,
+};
+
+use crate::{common::get_project_info_or_exit, context::Context};
+
+/// Show a project setting value
+#[derive(Parser)]
+pub struct Get {
+    /// Project path
+    #[clap(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Setting key (e.g. application/config/version)
+    key: String,
+}
+
+/// Set a project setting value
+#[derive(Parser)]
+pub struct Set {
+    /// Project path
+    #[clap(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Setting key (e.g. application/config/version)
+    key: String,
+
+    /// Value, in Godot literal syntax (e.g. "1.0.0" with quotes, 12, true, Vector2(1, 2))
+    value: String,
+
+    /// Store the value as a string, without parsing it
+    #[clap(short, long)]
+    string: bool,
+}
+
+/// Unset a project setting
+#[derive(Parser)]
+pub struct Unset {
+    /// Project path
+    #[clap(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Setting key (e.g. application/config/version)
+    key: String,
+}
+
+impl Get {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        get_project_info_or_exit(context, &self.path)?;
+        let phandler = ProjectHandler::new(context.io());
+        let value = phandler.get_project_setting(&self.path, &self.key)?;
+        write_stdout!(context.io(), "{}\n", value)?;
+
+        Ok(())
+    }
+}
+
+impl Set {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let info = get_project_info_or_exit(context, &self.path)?;
+        let phandler = ProjectHandler::new(context.io());
+        let value = if self.string {
+            GdValue::String(self.value)
+        } else {
+            match GdValue::from_str(&self.value) {
+                Ok(v) => v,
+                Err(e) => {
+                    write_stderr!(
+                        context.io(),
+                        "{}\n{}\n{}\n",
+                        "Invalid value, it should use Godot literal syntax.".color("red"),
+                        e,
+                        "Use `--string` to store it as a plain string.".color("yellow")
+                    )?;
+                    std::process::exit(1);
+                }
+            }
+        };
+
+        phandler.set_project_setting(&self.path, &self.key, value.clone())?;
+        write_stdout!(
+            context.io(),
+            "Setting {} set to {} for project {}.\n",
+            self.key.color("green"),
+            value.to_string().color("blue"),
+            info.get_versioned_name().color("green")
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Unset {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let info = get_project_info_or_exit(context, &self.path)?;
+        let phandler = ProjectHandler::new(context.io());
+        phandler.unset_project_setting(&self.path, &self.key)?;
+        write_stdout!(
+            context.io(),
+            "Setting {} removed from project {}.\n",
+            self.key.color("green"),
+            info.get_versioned_name().color("green")
+        )?;
+
+        Ok(())
+    }
+}
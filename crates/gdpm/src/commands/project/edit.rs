@@ -8,17 +8,18 @@ use gdpm_core::{
     downloader::DownloadAdapter,
     engine::EngineHandler,
     io::{write_stderr, write_stdout, IoAdapter},
+    pin::EngineSource,
     project::ProjectHandler,
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 use question::{Answer, Question};
 
 use crate::{
     commands::engine::add::Add,
     common::{
-        check_engine_version_or_ask_default, get_project_info_or_exit,
+        check_engine_version_or_ask_default, get_pinned_version_or_exit, get_project_info_or_exit,
         print_missing_default_engine_message, print_missing_project_engine_message,
-        validate_engine_version_or_exit, CheckEngineResponse,
+        resolve_engine_version_or_exit, CheckEngineResponse,
     },
     context::Context,
 };
@@ -29,19 +30,26 @@ pub struct Edit {
     #[clap(short, long, default_value = ".")]
     path: PathBuf,
 
-    /// Engine version
+    /// Engine version or requirement (e.g. 4.3, 4.x, ^4.2, latest)
     #[clap(short, long)]
-    engine: Option<GodotVersion>,
+    engine: Option<GodotVersionReq>,
 }
 
 impl Edit {
-    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+    pub fn execute<I: IoAdapter + Clone + Send + 'static, D: DownloadAdapter>(
+        self,
+        context: &Context<I, D>,
+    ) -> Result<()> {
         let info = get_project_info_or_exit(context, &self.path)?;
         let ehandler = EngineHandler::new(context.io());
         let phandler = ProjectHandler::new(context.io());
 
-        if let Some(v) = self.engine {
-            validate_engine_version_or_exit(context, &v)?;
+        // Exact project engine versions are handled below, to offer their installation
+        let pinned = get_pinned_version_or_exit(context, self.engine, &self.path)?
+            .filter(|(req, source)| *source != EngineSource::Project || req.exact().is_none());
+
+        if let Some((req, _)) = pinned {
+            let v = resolve_engine_version_or_exit(context, &req)?.version;
             write_stdout!(
                 context.io(),
                 "Running Godot Engine v{} for project {} ...\n",
@@ -49,7 +57,7 @@ impl Edit {
                 info.get_versioned_name().color("green")
             )?;
             ehandler.run_version_for_project(&v, &self.path)?;
-        } else if let Some(e) = info.get_engine_version() {
+        } else if let Some(e) = info.get_engine_version().and_then(|r| r.exact()) {
             let engine_response = check_engine_version_or_ask_default(context, e)?;
             let engine_version = match engine_response {
                 CheckEngineResponse::Found(v) => v,
@@ -60,12 +68,18 @@ impl Edit {
                 }
                 CheckEngineResponse::Download(v) => {
                     let cmd = Add {
-                        engine: v.clone(),
+                        engine: vec![v.clone().into()],
+                        pre_release: false,
+                        refresh: false,
                         headless: false,
                         overwrite: false,
                         server: false,
                         target_path: None,
                         target_url: None,
+                        sha256: None,
+                        sha512: None,
+                        mirror: vec![],
+                        skip_checksum: false,
                     };
 
                     cmd.execute(context)?;
@@ -89,7 +103,7 @@ impl Edit {
             ))
             .confirm()
             {
-                Answer::YES => phandler.set_project_engine(&self.path, &e)?,
+                Answer::YES => phandler.set_project_engine(&self.path, &e.clone().into())?,
                 Answer::NO => {
                     write_stdout!(context.io(), "Okay. You will be asked again next time.\n")?
                 }
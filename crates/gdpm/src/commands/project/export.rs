@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::Result;
+
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    error::ExportError,
+    export::{ExportHandler, ExportMode},
+    io::{write_stderr, write_stdout, IoAdapter},
+    types::version::GodotVersionReq,
+};
+
+use crate::{
+    common::{
+        get_engine_for_path_or_exit, get_project_info_or_exit, print_missing_default_engine_message,
+    },
+    context::Context,
+};
+
+/// Export project using associated engine version
+#[derive(Parser)]
+pub struct Export {
+    /// Project path
+    #[clap(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Export preset name (lists presets if missing)
+    preset: Option<String>,
+
+    /// Export all presets
+    #[clap(long, conflicts_with = "preset")]
+    all: bool,
+
+    /// Release export (default)
+    #[clap(long, conflicts_with = "debug")]
+    release: bool,
+
+    /// Debug export
+    #[clap(long)]
+    debug: bool,
+
+    /// Engine version or requirement (e.g. 4.3, 4.x, ^4.2, latest)
+    #[clap(short, long)]
+    engine: Option<GodotVersionReq>,
+}
+
+impl Export {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let info = get_project_info_or_exit(context, &self.path)?;
+        let xhandler = ExportHandler::new(context.io());
+        let presets = xhandler.list_presets(&self.path)?;
+
+        let presets = if self.all {
+            presets
+        } else if let Some(name) = &self.preset {
+            vec![xhandler.get_preset(&self.path, name)?]
+        } else {
+            if presets.is_empty() {
+                write_stdout!(
+                    context.io(),
+                    "{}\n",
+                    "No export preset defined.".color("yellow")
+                )?;
+            } else {
+                write_stdout!(context.io(), "Available export presets:\n")?;
+                for preset in presets {
+                    write_stdout!(context.io(), "- {}\n", preset.get_verbose_name())?;
+                }
+            }
+
+            return Ok(());
+        };
+
+        let engine = match get_engine_for_path_or_exit(context, self.engine, &self.path)? {
+            Some(e) => e,
+            None => {
+                print_missing_default_engine_message(context)?;
+                return Ok(());
+            }
+        };
+
+        if let Err(ExportError::MissingExportTemplates(v, path)) =
+            xhandler.ensure_export_templates(&engine.version)
+        {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Export templates for version '{}' are missing (expected at path '{}'). Use `engine templates add {}` to install them.",
+                    v,
+                    path.display(),
+                    v
+                )
+                .color("red")
+            )?;
+            std::process::exit(1);
+        }
+
+        let mode = if self.debug {
+            ExportMode::Debug
+        } else {
+            ExportMode::Release
+        };
+
+        let (mut exported, mut skipped) = (0, 0);
+        for preset in presets {
+            // Presets without export path can not be exported from the command line
+            if self.all && preset.export_path.is_none() {
+                write_stderr!(
+                    context.io(),
+                    "{}\n",
+                    format!(
+                        "Skipping preset '{}', which has no export path.",
+                        preset.name
+                    )
+                    .color("yellow")
+                )?;
+                skipped += 1;
+                continue;
+            }
+
+            write_stdout!(
+                context.io(),
+                "Exporting preset {} ({}) of project {} using Godot Engine v{} ...\n",
+                preset.name.color("green"),
+                mode.to_string().color("blue"),
+                info.get_versioned_name().color("green"),
+                engine.get_name()
+            )?;
+
+            let output = xhandler.export_preset(&engine.version, &self.path, &preset, mode)?;
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Preset '{}' exported to '{}'.",
+                    preset.name,
+                    output.display()
+                )
+                .color("green")
+            )?;
+            exported += 1;
+        }
+
+        if self.all {
+            write_stdout!(
+                context.io(),
+                "{} preset(s) exported, {} skipped.\n",
+                exported,
+                skipped
+            )?;
+        }
+
+        Ok(())
+    }
+}
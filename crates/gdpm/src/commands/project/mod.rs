@@ -1,6 +1,9 @@
+pub mod config;
 pub mod edit;
+pub mod export;
 pub mod info;
 pub mod new;
 pub mod run;
 pub mod set_engine;
 pub mod unset_engine;
+pub mod version;
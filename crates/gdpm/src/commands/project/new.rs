@@ -8,11 +8,14 @@ use gdpm_core::{
     engine::EngineHandler,
     io::{write_stderr, write_stdout, IoAdapter},
     scaffolder::{ProjectInfo, ProjectRenderer, Scaffolder},
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
 use crate::{
-    common::{print_missing_default_engine_message, validate_engine_version_or_exit},
+    common::{
+        print_missing_default_engine_message, resolve_engine_version_or_exit,
+        validate_engine_version_or_exit,
+    },
     context::Context,
 };
 
@@ -28,16 +31,16 @@ pub struct New {
     #[clap(short, long, default_value = "forward_plus")]
     renderer: ProjectRenderer,
 
-    /// Engine version
+    /// Engine version, requirement or alias
     #[clap(short, long)]
-    engine: Option<GodotVersion>,
+    engine: Option<GodotVersionReq>,
 }
 
 impl New {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
         let ehandler = EngineHandler::new(context.io());
-        let engine = if let Some(v) = self.engine {
-            validate_engine_version_or_exit(context, &v)?
+        let engine = if let Some(req) = self.engine {
+            resolve_engine_version_or_exit(context, &req)?
         } else if let Some(v) = ehandler.get_default()? {
             validate_engine_version_or_exit(context, &v)?
         } else {
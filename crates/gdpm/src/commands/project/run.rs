@@ -8,11 +8,11 @@ use gdpm_core::{
     downloader::DownloadAdapter,
     engine::EngineHandler,
     io::{write_stdout, IoAdapter},
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
 use crate::{
-    common::{print_missing_default_engine_message, validate_engine_version_or_exit},
+    common::{get_engine_for_path_or_exit, print_missing_default_engine_message},
     context::Context,
 };
 
@@ -22,28 +22,20 @@ pub struct Run {
     #[clap(short, long, default_value = ".")]
     path: PathBuf,
 
-    /// Engine version
-    engine: Option<GodotVersion>,
+    /// Engine version or requirement (e.g. 4.3, 4.x, ^4.2, latest)
+    engine: Option<GodotVersionReq>,
 }
 
 impl Run {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
         let ehandler = EngineHandler::new(context.io());
-        if let Some(v) = self.engine {
-            validate_engine_version_or_exit(context, &v)?;
+        if let Some(e) = get_engine_for_path_or_exit(context, self.engine, &self.path)? {
             write_stdout!(
                 context.io(),
                 "Running project using Godot Engine v{} ...\n",
-                v.to_string().color("green")
+                e.version.to_string().color("green")
             )?;
-            ehandler.run_version_for_project_no_editor(&v, &self.path)?;
-        } else if let Some(e) = ehandler.get_default()? {
-            write_stdout!(
-                context.io(),
-                "Running project using Godot Engine v{} ...\n",
-                e.to_string().color("green")
-            )?;
-            ehandler.run_version_for_project_no_editor(&e, &self.path)?;
+            ehandler.run_version_for_project_no_editor(&e.version, &self.path)?;
         } else {
             print_missing_default_engine_message(context)?;
         }
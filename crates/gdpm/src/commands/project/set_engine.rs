@@ -8,11 +8,11 @@ use gdpm_core::{
     downloader::DownloadAdapter,
     io::{write_stdout, IoAdapter},
     project::ProjectHandler,
-    types::version::GodotVersion,
+    types::version::GodotVersionReq,
 };
 
 use crate::{
-    common::{get_project_info_or_exit, validate_engine_version_or_exit},
+    common::{get_project_info_or_exit, resolve_engine_version_or_exit},
     context::Context,
 };
 
@@ -22,22 +22,42 @@ pub struct SetEngine {
     #[clap(short, long, default_value = ".")]
     path: PathBuf,
 
-    /// Engine version
-    engine: GodotVersion,
+    /// Engine version, requirement or alias (e.g. 4.3, 4.x, ^4.2, stable, lts)
+    ///
+    /// Aliases are stored as-is, so that updating them changes the project engine.
+    engine: GodotVersionReq,
+
+    /// Allow pre-release versions when resolving the engine requirement
+    #[clap(long)]
+    pre_release: bool,
 }
 
 impl SetEngine {
     pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
         let info = get_project_info_or_exit(context, &self.path)?;
         let phandler = ProjectHandler::new(context.io());
-        validate_engine_version_or_exit(context, &self.engine)?;
-        phandler.set_project_engine(&self.path, &self.engine)?;
-        write_stdout!(
-            context.io(),
-            "Godot Engine v{} set for project {}.\n",
-            self.engine.to_string().color("green"),
-            info.get_versioned_name().color("green")
+        let engine = resolve_engine_version_or_exit(
+            context,
+            &self.engine.clone().with_pre_release(self.pre_release),
         )?;
+        if let Some(alias) = self.engine.alias() {
+            phandler.set_project_engine(&self.path, &self.engine)?;
+            write_stdout!(
+                context.io(),
+                "Engine alias '{}' (Godot Engine v{}) set for project {}.\n",
+                alias.color("green"),
+                engine.version.to_string().color("green"),
+                info.get_versioned_name().color("green")
+            )?;
+        } else {
+            phandler.set_project_engine(&self.path, &engine.version.clone().into())?;
+            write_stdout!(
+                context.io(),
+                "Godot Engine v{} set for project {}.\n",
+                engine.version.to_string().color("green"),
+                info.get_versioned_name().color("green")
+            )?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::Result;
+
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    io::{write_stdout, IoAdapter},
+    project::{ProjectHandler, VersionBump},
+};
+
+use crate::{common::get_project_info_or_exit, context::Context};
+
+/// Show or bump project version
+#[derive(Parser)]
+pub struct Version {
+    /// Project path
+    #[clap(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Version bump: major, minor, patch or an explicit version (e.g. 1.2.0)
+    bump: Option<VersionBump>,
+
+    /// Also update version fields in export presets
+    #[clap(long)]
+    presets: bool,
+
+    /// Commit the version change and create a git tag
+    #[clap(long)]
+    tag: bool,
+
+    /// Git tag prefix
+    #[clap(long, default_value = "v")]
+    tag_prefix: String,
+}
+
+impl Version {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let info = get_project_info_or_exit(context, &self.path)?;
+
+        let Some(bump) = self.bump else {
+            match info.get_version() {
+                Some(v) => write_stdout!(context.io(), "{}\n", v)?,
+                None => write_stdout!(
+                    context.io(),
+                    "{}\n",
+                    "Project has no version.".color("yellow")
+                )?,
+            }
+
+            return Ok(());
+        };
+
+        let phandler = ProjectHandler::new(context.io());
+        let version = phandler.bump_project_version(&self.path, &bump)?;
+        write_stdout!(
+            context.io(),
+            "Project {} is now at version {}.\n",
+            info.get_versioned_name().color("green"),
+            version.to_string().color("green")
+        )?;
+
+        if self.presets {
+            let count = phandler.bump_export_presets_version(&self.path, &version)?;
+            write_stdout!(
+                context.io(),
+                "Updated version in {} export preset(s).\n",
+                count.to_string().color("green")
+            )?;
+        }
+
+        if self.tag {
+            let tag = phandler.tag_project_version(&self.path, &version, &self.tag_prefix)?;
+            write_stdout!(context.io(), "Created git tag {}.\n", tag.color("green"))?;
+        }
+
+        Ok(())
+    }
+}
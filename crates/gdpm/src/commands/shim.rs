@@ -0,0 +1,129 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use clap::Parser;
+use color_eyre::Result;
+
+use colored::Colorize;
+use gdpm_core::{
+    downloader::DownloadAdapter,
+    engine::EngineHandler,
+    io::{write_stderr, write_stdout, IoAdapter},
+    project::ProjectHandler,
+    shim::ShimHandler,
+};
+
+use crate::{common::get_engine_for_path_or_exit, context::Context};
+
+/// Install a `godot` shim running the engine associated to the current project
+#[derive(Parser)]
+pub struct Install {
+    /// Target directory (defaults to the `shims` folder in the gdpm configuration directory)
+    #[clap(short, long)]
+    dir: Option<PathBuf>,
+}
+
+/// Remove the `godot` shim
+#[derive(Parser)]
+pub struct Uninstall {
+    /// Target directory (defaults to the `shims` folder in the gdpm configuration directory)
+    #[clap(short, long)]
+    dir: Option<PathBuf>,
+}
+
+/// Run the engine associated to the current project (called by the shim)
+#[derive(Parser)]
+pub struct Exec {
+    /// Engine arguments
+    #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<OsString>,
+}
+
+impl Install {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let shandler = ShimHandler::new(context.io());
+        let dir = match self.dir {
+            Some(d) => d,
+            None => shandler.get_or_create_default_directory()?,
+        };
+
+        let gdpm_path = std::env::current_exe()?;
+        let path = shandler.install(&dir, &gdpm_path)?;
+        write_stdout!(
+            context.io(),
+            "{}\n",
+            format!("Shim installed at path '{}'.", path.display()).color("green")
+        )?;
+
+        let in_path = std::env::var_os("PATH")
+            .map(|p| std::env::split_paths(&p).any(|p| p == dir))
+            .unwrap_or(false);
+        if !in_path {
+            write_stdout!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Add '{}' at the start of your PATH to use it.",
+                    dir.display()
+                )
+                .color("yellow")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Uninstall {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let shandler = ShimHandler::new(context.io());
+        let dir = match self.dir {
+            Some(d) => d,
+            None => shandler.get_or_create_default_directory()?,
+        };
+
+        match shandler.uninstall(&dir)? {
+            Some(path) => write_stdout!(
+                context.io(),
+                "Shim removed from path '{}'.\n",
+                path.display()
+            )?,
+            None => write_stdout!(
+                context.io(),
+                "{}\n",
+                format!("No shim installed in '{}'.", dir.display()).color("yellow")
+            )?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Exec {
+    pub fn execute<I: IoAdapter, D: DownloadAdapter>(self, context: &Context<I, D>) -> Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let phandler = ProjectHandler::new(context.io());
+        let project_dir = phandler
+            .find_project_dir(&current_dir)
+            .unwrap_or(current_dir);
+
+        let ehandler = EngineHandler::new(context.io());
+        match get_engine_for_path_or_exit(context, None, &project_dir)? {
+            Some(engine) => {
+                let code = ehandler.exec_version(&engine.version, &self.args)?;
+                std::process::exit(code);
+            }
+            None => {
+                write_stderr!(
+                    context.io(),
+                    "{}\n",
+                    format!(
+                        "No engine associated to '{}' and no default engine registered.",
+                        project_dir.display()
+                    )
+                    .color("red")
+                )?;
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -5,13 +5,16 @@ use colored::Colorize;
 use gdpm_core::{
     downloader::DownloadAdapter,
     engine::{EngineHandler, EngineInfo},
+    error::{EngineError, ProjectError},
     io::{write_stderr, write_stdout, IoAdapter},
+    pin::{EngineSource, PinHandler},
     project::{GdProjectInfo, ProjectHandler},
-    types::version::{GodotVersion, SystemVersion},
+    types::version::{GodotBuildInfo, GodotVersion, GodotVersionReq, SystemVersion},
 };
 use question::{Answer, Question};
+use tracing::debug;
 
-use crate::context::Context;
+use crate::{commands::engine::list_remote::ListRemote, context::Context};
 
 pub enum CheckEngineResponse {
     Found(EngineInfo),
@@ -43,6 +46,26 @@ pub(crate) fn print_missing_project_engine_message<I: IoAdapter, D: DownloadAdap
     Ok(())
 }
 
+pub(crate) fn print_build_mismatch_warning<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    engine: &EngineInfo,
+    build: &GodotBuildInfo,
+) -> Result<()> {
+    write_stderr!(
+        context.io(),
+        "{}\n",
+        format!(
+            "Engine '{}' at path '{}' reports version '{}' (build '{}'), which does not match its registration.",
+            engine.version,
+            engine.path.display(),
+            build.version,
+            build.hash.as_deref().unwrap_or("unknown")
+        )
+        .color("yellow")
+    )?;
+    Ok(())
+}
+
 pub(crate) fn get_project_info_or_exit<I: IoAdapter, D: DownloadAdapter>(
     context: &Context<I, D>,
     project_path: &Path,
@@ -50,6 +73,15 @@ pub(crate) fn get_project_info_or_exit<I: IoAdapter, D: DownloadAdapter>(
     let phandler = ProjectHandler::new(context.io());
     match phandler.get_project_info(project_path) {
         Ok(info) => Ok(info),
+        Err(ProjectError::MalformedProject(e)) => {
+            write_stderr!(
+                context.io(),
+                "{}\n{}\n",
+                "Godot project file is malformed.".color("red"),
+                e
+            )?;
+            std::process::exit(1);
+        }
         Err(_) => {
             if project_path.to_str() == Some(".") {
                 write_stdout!(
@@ -109,8 +141,97 @@ pub(crate) fn validate_engine_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
     context: &Context<I, D>,
     version: &GodotVersion,
 ) -> Result<EngineInfo> {
+    resolve_engine_version_or_exit(context, &version.clone().into())
+}
+
+/// Expand an engine alias from the `[aliases]` configuration section.
+pub(crate) fn expand_alias_or_exit<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    req: &GodotVersionReq,
+) -> Result<GodotVersionReq> {
     let ehandler = EngineHandler::new(context.io());
-    match ehandler.get_version(version) {
+    match ehandler.expand_alias(req) {
+        Ok(r) => {
+            if req.alias().is_some() {
+                debug!("Expanded alias '{}' to '{}'.", req, r);
+            }
+            Ok(r)
+        }
+        Err(e @ (EngineError::UnknownAlias(_) | EngineError::InvalidAlias(..))) => {
+            write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+            std::process::exit(1);
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Resolve a release channel (`latest`, `stable`, `beta`) using remote versions.
+///
+/// Returns `None` if remote versions can not be fetched.
+pub(crate) fn resolve_channel_version<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    req: &GodotVersionReq,
+) -> Result<Option<GodotVersion>> {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    let remote_versions = match rt.block_on(ListRemote::lookup_remote_versions(context, false)) {
+        Ok(v) => v,
+        Err(e) => {
+            write_stderr!(
+                context.io(),
+                "{}\n",
+                format!(
+                    "Could not fetch remote versions to resolve '{}', using installed engines.\n    | {}",
+                    req, e
+                )
+                .color("yellow")
+            )?;
+            return Ok(None);
+        }
+    };
+
+    // Remote releases are listed without their mono variant
+    let candidates: Vec<_> = remote_versions
+        .iter()
+        .map(|v| GodotVersion::new(v.version(), v.kind().clone(), req.mono()))
+        .collect();
+
+    Ok(req.resolve(&candidates))
+}
+
+/// Get an exact engine version from a version, an alias or a requirement
+/// (resolved against installed engines).
+pub(crate) fn get_exact_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    req: &GodotVersionReq,
+) -> Result<GodotVersion> {
+    let expanded = expand_alias_or_exit(context, req)?;
+    match expanded.exact() {
+        Some(v) => Ok(v.clone()),
+        None => Ok(resolve_engine_version_or_exit(context, &expanded)?.version),
+    }
+}
+
+pub(crate) fn resolve_engine_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    req: &GodotVersionReq,
+) -> Result<EngineInfo> {
+    let ehandler = EngineHandler::new(context.io());
+    let expanded = expand_alias_or_exit(context, req)?;
+
+    if expanded.is_channel() {
+        if let Some(version) = resolve_channel_version(context, &expanded)? {
+            if let Some(engine) = ehandler.has_version(&version)? {
+                return Ok(engine);
+            }
+
+            write_stdout!(context.io(), "{}", format!("Channel `{}` points to engine version `{}`, which is not installed. You can install it with `engine add {}`.\n", expanded, version.to_string().color("green"), version).color("yellow"))?;
+            std::process::exit(1);
+        }
+    }
+
+    match ehandler.resolve(&expanded) {
         Ok(v) => Ok(v),
         Err(_) => {
             let available_engines = ehandler.list()?;
@@ -119,7 +240,11 @@ pub(crate) fn validate_engine_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
                 .map(|x| format!("- {}", x.get_verbose_name().color("green")))
                 .collect();
 
-            write_stdout!(context.io(), "{}", format!("Unknown engine with version `{}`. You need to `engine register` this version before using it.\n", version.to_string().color("green")).color("yellow"))?;
+            if let Some(version) = expanded.exact() {
+                write_stdout!(context.io(), "{}", format!("Unknown engine with version `{}`. You need to `engine register` this version before using it.\n", version.to_string().color("green")).color("yellow"))?;
+            } else {
+                write_stdout!(context.io(), "{}", format!("No installed engine matches `{}`. You can install one with `engine add {}`.\n", expanded.to_string().color("green"), expanded).color("yellow"))?;
+            }
 
             if available_engine_names.is_empty() {
                 write_stdout!(
@@ -138,13 +263,61 @@ pub(crate) fn validate_engine_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
     }
 }
 
+/// Get the engine version requirement for a path, from the command line argument,
+/// `GDPM_ENGINE`, the nearest `.godot-version` file or the project file.
+pub(crate) fn get_pinned_version_or_exit<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    engine: Option<GodotVersionReq>,
+    path: &Path,
+) -> Result<Option<(GodotVersionReq, EngineSource)>> {
+    if let Some(req) = engine {
+        return Ok(Some((req, EngineSource::Argument)));
+    }
+
+    let pin_handler = PinHandler::new(context.io());
+    match pin_handler.get_pinned_version(path) {
+        Ok(pinned) => Ok(pinned),
+        Err(e @ EngineError::InvalidPinnedVersion(..)) => {
+            write_stderr!(context.io(), "{}\n", e.to_string().color("red"))?;
+            std::process::exit(1);
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get the engine to use for a path, following the pinning precedence and
+/// falling back to the default engine.
+pub(crate) fn get_engine_for_path_or_exit<I: IoAdapter, D: DownloadAdapter>(
+    context: &Context<I, D>,
+    engine: Option<GodotVersionReq>,
+    path: &Path,
+) -> Result<Option<EngineInfo>> {
+    let ehandler = EngineHandler::new(context.io());
+    let (req, source) = match get_pinned_version_or_exit(context, engine, path)? {
+        Some(pinned) => pinned,
+        None => match ehandler.get_default()? {
+            Some(v) => (v.into(), EngineSource::Default),
+            None => return Ok(None),
+        },
+    };
+
+    let engine = resolve_engine_version_or_exit(context, &req)?;
+    debug!(
+        "Using engine version '{}' from {}.",
+        engine.version.to_string().color("green"),
+        source
+    );
+
+    Ok(Some(engine))
+}
+
 pub(crate) fn parse_godot_version_args<I: IoAdapter, D: DownloadAdapter>(
     context: &Context<I, D>,
     version: &GodotVersion,
     headless: bool,
     server: bool,
 ) -> Result<(GodotVersion, SystemVersion)> {
-    let system = SystemVersion::determine_system_kind();
+    let system = SystemVersion::determine_system_kind()?;
 
     if !system.is_linux() && headless {
         write_stderr!(
@@ -162,7 +335,51 @@ pub(crate) fn parse_godot_version_args<I: IoAdapter, D: DownloadAdapter>(
                 .color("red")
         )?;
         std::process::exit(1);
+    } else if matches!(system, SystemVersion::LinuxArm64) && (headless || server) {
+        write_stderr!(
+            context.io(),
+            "{}",
+            "Headless and server versions of Godot Engine are only available for x86_64 Linux.\n"
+                .color("red")
+        )?;
+        std::process::exit(1);
+    } else if version.major() >= 4 && (headless || server) {
+        write_stderr!(
+            context.io(),
+            "{}",
+            "Godot Engine 4 has no headless or server version, use the '--headless' engine argument instead.\n"
+                .color("red")
+        )?;
+        std::process::exit(1);
     }
 
+    let system = if headless {
+        SystemVersion::LinuxHeadless64
+    } else if server {
+        SystemVersion::LinuxServer64
+    } else {
+        system
+    };
+
     Ok((version.clone(), system))
 }
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for u in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = u;
+    }
+
+    format!("{:.1} {}", size, unit)
+}
@@ -3,7 +3,11 @@ use color_eyre::Result;
 use colored::Colorize;
 use commands::args::{parse_args, Args};
 use context::Context;
-use gdpm_core::{downloader::DefaultDownloadAdapter, io::DefaultIoAdapter};
+use gdpm_core::{
+    config::GlobalConfig,
+    downloader::{config::DownloadConfig, DefaultDownloadAdapter},
+    io::DefaultIoAdapter,
+};
 
 mod commands;
 mod common;
@@ -13,7 +17,19 @@ pub fn run() -> Result<()> {
     color_eyre::install()?;
 
     let args: Args = Args::parse();
-    let ctx = Context::new(DefaultIoAdapter, DefaultDownloadAdapter);
+    let download_config = GlobalConfig::new(&DefaultIoAdapter)
+        .load_download_config()
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "{}",
+                format!("Could not load download settings, using defaults: {e}").yellow()
+            );
+            DownloadConfig::default()
+        });
+    let ctx = Context::new(
+        DefaultIoAdapter,
+        DefaultDownloadAdapter::new(download_config)?,
+    );
 
     if let Err(e) = parse_args(ctx, args) {
         eprintln!();
@@ -1,10 +1,52 @@
 use std::{
     num::{ParseFloatError, ParseIntError},
+    path::{Path, PathBuf},
     str::ParseBoolError,
 };
 
+use pest::{error::LineColLocation, RuleType};
 use thiserror::Error;
 
+/// Parser diagnostic, pointing to a location in the parsed source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserDiagnostic {
+    /// Source file path, if known
+    pub path: Option<PathBuf>,
+    /// Line number (starting at 1)
+    pub line: usize,
+    /// Column number (starting at 1)
+    pub column: usize,
+    /// Contents of the offending line
+    pub source_line: String,
+    /// Error message
+    pub message: String,
+}
+
+impl std::fmt::Display for ParserDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let location = match &self.path {
+            Some(p) => format!("{}:{}:{}", p.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        };
+
+        // Keep tabs in the caret line so it stays aligned with the source line
+        let caret_offset: String = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{}--> {}", gutter, location)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, self.source_line)?;
+        write!(f, "{} | {}^", gutter, caret_offset)
+    }
+}
+
 /// Parser error
 #[derive(Debug, Error)]
 pub enum ParserError {
@@ -12,21 +54,58 @@ pub enum ParserError {
     #[error("Parse error")]
     ParseError,
 
-    /// Parse error
-    #[error("Parse error: {0}")]
-    PestError(String),
+    /// Syntax error, with its location in the source
+    #[error("{0}")]
+    SyntaxError(ParserDiagnostic),
 
     /// Type conversion error
     #[error("Type conversion error: '{0}'.")]
     TypeConversionError(String),
 }
 
-impl<E> From<pest::error::Error<E>> for ParserError
+impl ParserError {
+    /// Attach a source file path to the error diagnostic, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Source file path
+    ///
+    pub fn with_path(self, path: &Path) -> Self {
+        match self {
+            Self::SyntaxError(mut diagnostic) => {
+                diagnostic.path = Some(path.to_owned());
+                Self::SyntaxError(diagnostic)
+            }
+            e => e,
+        }
+    }
+
+    /// Get the error diagnostic, if any.
+    pub fn diagnostic(&self) -> Option<&ParserDiagnostic> {
+        match self {
+            Self::SyntaxError(d) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+impl<R> From<pest::error::Error<R>> for ParserError
 where
-    E: std::fmt::Debug,
+    R: RuleType,
 {
-    fn from(error: pest::error::Error<E>) -> Self {
-        Self::PestError(format!("{:?}", error))
+    fn from(error: pest::error::Error<R>) -> Self {
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(pos) => pos,
+            LineColLocation::Span(start, _) => start,
+        };
+
+        Self::SyntaxError(ParserDiagnostic {
+            path: error.path().map(PathBuf::from),
+            line,
+            column,
+            source_line: error.line().trim_end_matches(['\r', '\n']).to_string(),
+            message: error.variant.message().to_string(),
+        })
     }
 }
 
@@ -1,6 +1,8 @@
 //! GDValue
 
-use std::{collections::BTreeMap, string::ToString};
+use std::{collections::BTreeMap, str::FromStr, string::ToString};
+
+use crate::{parser::parse_gdvalue_str, ParserError};
 
 /// Godot value
 #[derive(PartialEq, Debug, Clone)]
@@ -11,8 +13,10 @@ pub enum GdValue {
     Array(Vec<GdValue>),
     /// String
     String(String),
+    /// String name (e.g. `&"idle"`)
+    StringName(String),
     /// Int
-    Int(i32),
+    Int(i64),
     /// Float
     Float(f64),
     /// Boolean
@@ -21,6 +25,8 @@ pub enum GdValue {
     ClassName(String),
     /// Class instance: a name, arguments and keyword arguments
     ClassInstance(String, Vec<GdValue>, Vec<(String, GdValue)>),
+    /// Typed array: an element type (class name or instance, e.g. `ExtResource("1_abc")`) and values
+    TypedArray(Box<GdValue>, Vec<GdValue>),
     /// Null
     Null,
 }
@@ -58,8 +64,13 @@ impl GdValue {
         }
     }
 
-    /// To i32
+    /// To i32, if the value fits
     pub fn to_i32(&self) -> Option<i32> {
+        self.to_i64().and_then(|i| i32::try_from(i).ok())
+    }
+
+    /// To i64
+    pub fn to_i64(&self) -> Option<i64> {
         if let GdValue::Int(i) = &self {
             Some(*i)
         } else {
@@ -92,6 +103,14 @@ impl std::fmt::Display for GdValue {
     }
 }
 
+impl FromStr for GdValue {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_gdvalue_str(s)
+    }
+}
+
 /// Serialize a GdValue to string
 ///
 /// # Arguments
@@ -129,9 +148,19 @@ fn serialize_gdvalue(val: &GdValue) -> String {
                 format!("{}({}, {})", cls, args_content, kwargs_content)
             }
         }
+        GdValue::TypedArray(kind, a) => {
+            let contents: Vec<_> = a.iter().map(serialize_gdvalue).collect();
+            format!(
+                "Array[{}]([{}])",
+                serialize_gdvalue(kind),
+                contents.join(", ")
+            )
+        }
         GdValue::ClassName(n) => n.to_string(),
         GdValue::String(s) => format!("\"{}\"", s),
+        GdValue::StringName(s) => format!("&\"{}\"", s),
         GdValue::Int(n) => n.to_string(),
+        GdValue::Float(n) if n.is_nan() => "nan".to_string(),
         GdValue::Float(n) => format!("{:.9}", n),
         GdValue::Boolean(b) => b.to_string(),
         GdValue::Null => "null".to_string(),
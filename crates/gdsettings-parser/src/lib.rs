@@ -5,9 +5,15 @@
 mod error;
 mod gdvalue;
 mod parser;
+mod resource;
 
-pub use error::{GdSettingsError, ParserError};
+pub use error::{GdSettingsError, ParserDiagnostic, ParserError};
 pub use gdvalue::GdValue;
 pub use parser::{
-    parse_gdsettings_file, serialize_gdsettings, GdSettings, GdSettingsMap, GdSettingsType,
+    parse_gdsettings_file, parse_gdvalue_str, serialize_gdsettings, GdSettings, GdSettingsMap,
+    GdSettingsType,
+};
+pub use resource::{
+    parse_gdresource_file, serialize_gdresource, GdExtResource, GdResource, GdResourceSection,
+    EXT_RESOURCE_SECTION, SUB_RESOURCE_SECTION,
 };
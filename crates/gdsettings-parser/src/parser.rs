@@ -2,7 +2,7 @@
 
 use std::{collections::BTreeMap, str::FromStr};
 
-use pest::Parser;
+use pest::{error::ErrorVariant, iterators::Pair, Parser};
 use pest_derive::Parser;
 
 use crate::{GdSettingsError, GdValue, ParserError};
@@ -10,7 +10,7 @@ use crate::{GdSettingsError, GdValue, ParserError};
 /// Parser
 #[derive(Parser)]
 #[grammar = "gdsettings.pest"]
-struct GdSettingsParser;
+pub(crate) struct GdSettingsParser;
 
 /// Godot settings map
 pub type GdSettingsMap = BTreeMap<String, GdValue>;
@@ -152,6 +152,118 @@ pub fn serialize_gdsettings(settings: &GdSettings) -> String {
     output
 }
 
+/// Parse a scalar value, reporting conversion errors at the value location
+fn parse_scalar<T>(pair: &Pair<Rule>) -> Result<T, ParserError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    pair.as_str().parse().map_err(|e: T::Err| {
+        pest::error::Error::new_from_span(
+            ErrorVariant::<Rule>::CustomError {
+                message: format!("Type conversion error: '{}'.", e),
+            },
+            pair.as_span(),
+        )
+        .into()
+    })
+}
+
+/// Strip the surrounding quotes of a string literal
+fn unquote(value: &str) -> &str {
+    &value[1..value.len() - 1]
+}
+
+/// Parse a Godot value from a parser pair
+///
+/// # Arguments
+///
+/// * `pair` - Value pair
+///
+pub(crate) fn parse_gdvalue(pair: Pair<Rule>) -> Result<GdValue, ParserError> {
+    let value = match pair.as_rule() {
+        Rule::object => GdValue::Object(
+            pair.into_inner()
+                .map(|pair| {
+                    let mut inner_rules = pair.into_inner();
+                    let name = unquote(inner_rules.next().ok_or(ParserError::ParseError)?.as_str());
+                    let value = parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
+                    Ok((name.to_string(), value))
+                })
+                .collect::<Result<Vec<(String, GdValue)>, ParserError>>()?,
+        ),
+        Rule::array => GdValue::Array(
+            pair.into_inner()
+                .map(parse_gdvalue)
+                .collect::<Result<Vec<GdValue>, ParserError>>()?,
+        ),
+        Rule::string => GdValue::String(unquote(pair.as_str()).to_string()),
+        Rule::string_name => GdValue::StringName(unquote(&pair.as_str()[1..]).to_string()),
+        Rule::class_name => GdValue::ClassName(pair.as_str().to_string()),
+        Rule::class_instance => {
+            let mut inner_rules = pair.into_inner();
+            let class_name = inner_rules
+                .next()
+                .ok_or(ParserError::ParseError)?
+                .as_str()
+                .to_string();
+            let mut args = vec![];
+            let mut kwargs = vec![];
+
+            for pair in inner_rules {
+                match pair.as_rule() {
+                    // Check for kwarg
+                    Rule::pair => {
+                        let mut inner_rules = pair.into_inner();
+                        let name = inner_rules.next().ok_or(ParserError::ParseError)?.as_str();
+                        let value =
+                            parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
+                        kwargs.push((name.to_string(), value))
+                    }
+                    // Else convert
+                    _ => args.push(parse_gdvalue(pair)?),
+                }
+            }
+
+            GdValue::ClassInstance(class_name, args, kwargs)
+        }
+        Rule::typed_array => {
+            let mut inner_rules = pair.into_inner();
+            let kind = parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
+            let values = inner_rules
+                .next()
+                .ok_or(ParserError::ParseError)?
+                .into_inner()
+                .map(parse_gdvalue)
+                .collect::<Result<Vec<GdValue>, ParserError>>()?;
+
+            GdValue::TypedArray(Box::new(kind), values)
+        }
+        Rule::int => GdValue::Int(parse_scalar(&pair)?),
+        Rule::float => GdValue::Float(parse_scalar(&pair)?),
+        Rule::boolean => GdValue::Boolean(parse_scalar(&pair)?),
+        Rule::null => GdValue::Null,
+        _ => unreachable!(),
+    };
+
+    Ok(value)
+}
+
+/// Parse a standalone Godot value, in Godot literal syntax
+///
+/// # Arguments
+///
+/// * `contents` - Value contents
+///
+pub fn parse_gdvalue_str(contents: &str) -> Result<GdValue, ParserError> {
+    let value = GdSettingsParser::parse(Rule::single_value, contents.trim())?
+        .next()
+        .and_then(|pair| pair.into_inner().next())
+        .ok_or(ParserError::ParseError)?;
+
+    parse_gdvalue(value)
+}
+
 /// Parse Godot settings file
 ///
 /// # Arguments
@@ -159,75 +271,12 @@ pub fn serialize_gdsettings(settings: &GdSettings) -> String {
 /// * `contents` - File contents
 ///
 pub fn parse_gdsettings_file(contents: &str) -> Result<GdSettings, ParserError> {
-    use pest::iterators::Pair;
-
     let data = GdSettingsParser::parse(Rule::file, contents)?
         .next()
         .ok_or(ParserError::ParseError)?;
     let mut properties: GdSettingsType = BTreeMap::new();
     let mut current_section = "";
 
-    fn parse_gdvalue(pair: Pair<Rule>) -> Result<GdValue, ParserError> {
-        let value = match pair.as_rule() {
-            Rule::object => GdValue::Object(
-                pair.into_inner()
-                    .map(|pair| {
-                        let mut inner_rules = pair.into_inner();
-                        let name = inner_rules
-                            .next()
-                            .ok_or(ParserError::ParseError)?
-                            .as_str()
-                            .trim_matches('"');
-                        let value =
-                            parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
-                        Ok((name.to_string(), value))
-                    })
-                    .collect::<Result<Vec<(String, GdValue)>, ParserError>>()?,
-            ),
-            Rule::array => GdValue::Array(
-                pair.into_inner()
-                    .map(parse_gdvalue)
-                    .collect::<Result<Vec<GdValue>, ParserError>>()?,
-            ),
-            Rule::string => GdValue::String(pair.as_str().trim_matches('"').to_string()),
-            Rule::class_name => GdValue::ClassName(pair.as_str().to_string()),
-            Rule::class_instance => {
-                let mut inner_rules = pair.into_inner();
-                let class_name = inner_rules
-                    .next()
-                    .ok_or(ParserError::ParseError)?
-                    .as_str()
-                    .to_string();
-                let mut args = vec![];
-                let mut kwargs = vec![];
-
-                for pair in inner_rules {
-                    match pair.as_rule() {
-                        // Check for kwarg
-                        Rule::pair => {
-                            let mut inner_rules = pair.into_inner();
-                            let name = inner_rules.next().ok_or(ParserError::ParseError)?.as_str();
-                            let value =
-                                parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
-                            kwargs.push((name.to_string(), value))
-                        }
-                        // Else convert
-                        _ => args.push(parse_gdvalue(pair)?),
-                    }
-                }
-
-                GdValue::ClassInstance(class_name, args, kwargs)
-            }
-            Rule::int => GdValue::Int(pair.as_str().parse()?),
-            Rule::float => GdValue::Float(pair.as_str().parse()?),
-            Rule::boolean => GdValue::Boolean(pair.as_str().parse()?),
-            Rule::null => GdValue::Null,
-            _ => unreachable!(),
-        };
-
-        Ok(value)
-    }
-
     for line in data.into_inner() {
         match line.as_rule() {
             Rule::section => {
@@ -352,4 +401,90 @@ environment/default_environment="res://default_env.tres""###;
 
         GdSettingsParser::parse(Rule::file, content).expect("failed to parse");
     }
+
+    #[test]
+    fn value_parser_test() {
+        assert_eq!(
+            parse_gdvalue_str("\"1.2.0\"").unwrap(),
+            GdValue::String("1.2.0".into())
+        );
+        assert_eq!(parse_gdvalue_str(" 12 ").unwrap(), GdValue::Int(12));
+        assert_eq!(
+            parse_gdvalue_str("Vector2i(1280, 720)").unwrap(),
+            GdValue::ClassInstance(
+                "Vector2i".into(),
+                vec![GdValue::Int(1280), GdValue::Int(720)],
+                vec![]
+            )
+        );
+        assert!(parse_gdvalue_str("1.2.0").is_err());
+        assert!(parse_gdvalue_str("1 2").is_err());
+    }
+
+    #[test]
+    fn class_arg_parser_test() {
+        // Keywords are values, identifiers starting with them are class names
+        assert_eq!(
+            parse_gdvalue_str("Object(inf, infinite, null, nullable, true, Trueish, nanny, 12)")
+                .unwrap(),
+            GdValue::ClassInstance(
+                "Object".into(),
+                vec![
+                    GdValue::Float(f64::INFINITY),
+                    GdValue::ClassName("infinite".into()),
+                    GdValue::Null,
+                    GdValue::ClassName("nullable".into()),
+                    GdValue::Boolean(true),
+                    GdValue::ClassName("Trueish".into()),
+                    GdValue::ClassName("nanny".into()),
+                    GdValue::Int(12),
+                ],
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse_gdvalue_str("Array[int]([1, 2])").unwrap(),
+            GdValue::TypedArray(
+                Box::new(GdValue::ClassName("int".into())),
+                vec![GdValue::Int(1), GdValue::Int(2)]
+            )
+        );
+    }
+
+    #[test]
+    fn diagnostic_parser_test() {
+        let content = "\
+[application]
+
+config/name=\"Project\"
+<<<<<<< HEAD
+config/version=\"1.0\"";
+
+        let error = parse_gdsettings_file(content)
+            .unwrap_err()
+            .with_path(Path::new("project.godot"));
+        let diagnostic = error.diagnostic().unwrap();
+
+        assert_eq!(diagnostic.path, Some(Path::new("project.godot").to_owned()));
+        assert_eq!(diagnostic.line, 4);
+        assert_eq!(diagnostic.column, 1);
+        assert_eq!(diagnostic.source_line, "<<<<<<< HEAD");
+        assert!(error
+            .to_string()
+            .contains(" --> project.godot:4:1\n  |\n4 | <<<<<<< HEAD\n  | ^"));
+    }
+
+    #[test]
+    fn diagnostic_conversion_test() {
+        let content = "\
+[application]
+config/count=99999999999999999999";
+
+        let error = parse_gdsettings_file(content).unwrap_err();
+        let diagnostic = error.diagnostic().unwrap();
+
+        assert_eq!(diagnostic.line, 2);
+        assert_eq!(diagnostic.column, 14);
+        assert!(diagnostic.message.starts_with("Type conversion error"));
+    }
 }
@@ -0,0 +1,464 @@
+//! Godot resource file parser (`.tscn` / `.tres`)
+
+use std::str::FromStr;
+
+use pest::{error::ErrorVariant, Parser};
+
+use crate::{
+    parser::{parse_gdvalue, GdSettingsParser, Rule},
+    GdSettingsError, GdValue, ParserError,
+};
+
+/// External resource section name.
+pub const EXT_RESOURCE_SECTION: &str = "ext_resource";
+/// Sub-resource section name.
+pub const SUB_RESOURCE_SECTION: &str = "sub_resource";
+
+/// Godot resource section (e.g. `[ext_resource type="Script" path="res://a.gd" id="1"]`)
+#[derive(PartialEq, Debug, Clone)]
+pub struct GdResourceSection {
+    name: String,
+    attributes: Vec<(String, GdValue)>,
+    properties: Vec<(String, GdValue)>,
+}
+
+impl GdResourceSection {
+    /// Create a new empty section
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Section name
+    ///
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: vec![],
+            properties: vec![],
+        }
+    }
+
+    /// Get section name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get attributes, in file order
+    pub fn attributes(&self) -> &[(String, GdValue)] {
+        &self.attributes
+    }
+
+    /// Get properties, in file order
+    pub fn properties(&self) -> &[(String, GdValue)] {
+        &self.properties
+    }
+
+    /// Get attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - Attribute name
+    ///
+    pub fn get_attribute(&self, attribute: &str) -> Option<GdValue> {
+        get_entry(&self.attributes, attribute)
+    }
+
+    /// Set attribute, keeping its position if it already exists
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - Attribute name
+    /// * `value` - Attribute value
+    ///
+    pub fn set_attribute(&mut self, attribute: &str, value: GdValue) {
+        set_entry(&mut self.attributes, attribute, value)
+    }
+
+    /// Remove attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - Attribute name
+    ///
+    pub fn remove_attribute(&mut self, attribute: &str) -> Result<(), GdSettingsError> {
+        remove_entry(&mut self.attributes, attribute)
+    }
+
+    /// Get property
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - Property name
+    ///
+    pub fn get_property(&self, property: &str) -> Option<GdValue> {
+        get_entry(&self.properties, property)
+    }
+
+    /// Set property, keeping its position if it already exists
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - Property name
+    /// * `value` - Property value
+    ///
+    pub fn set_property(&mut self, property: &str, value: GdValue) {
+        set_entry(&mut self.properties, property, value)
+    }
+
+    /// Remove property
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - Property name
+    ///
+    pub fn remove_property(&mut self, property: &str) -> Result<(), GdSettingsError> {
+        remove_entry(&mut self.properties, property)
+    }
+}
+
+fn get_entry(entries: &[(String, GdValue)], name: &str) -> Option<GdValue> {
+    entries
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.clone())
+}
+
+fn set_entry(entries: &mut Vec<(String, GdValue)>, name: &str, value: GdValue) {
+    if let Some(entry) = entries.iter_mut().find(|(k, _)| k == name) {
+        entry.1 = value;
+    } else {
+        entries.push((name.to_string(), value));
+    }
+}
+
+fn remove_entry(entries: &mut Vec<(String, GdValue)>, name: &str) -> Result<(), GdSettingsError> {
+    let len = entries.len();
+    entries.retain(|(k, _)| k != name);
+    if entries.len() == len {
+        return Err(GdSettingsError::MissingProperty(name.to_string()));
+    }
+
+    Ok(())
+}
+
+/// External resource reference
+#[derive(PartialEq, Debug, Clone)]
+pub struct GdExtResource {
+    /// Resource identifier, used by `ExtResource("<id>")` values
+    pub id: String,
+    /// Resource type (e.g. `Script`, `PackedScene`)
+    pub resource_type: String,
+    /// Resource path (e.g. `res://player.gd`)
+    pub path: String,
+    /// Resource UID, if any
+    pub uid: Option<String>,
+}
+
+impl GdExtResource {
+    /// Extract external resource from section
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - `ext_resource` section
+    ///
+    pub fn from_section(section: &GdResourceSection) -> Option<Self> {
+        if section.name() != EXT_RESOURCE_SECTION {
+            return None;
+        }
+
+        // Identifiers are integers in Godot 3 and strings in Godot 4
+        let id = match section.get_attribute("id")? {
+            GdValue::Int(i) => i.to_string(),
+            GdValue::String(s) => s,
+            _ => return None,
+        };
+
+        Some(Self {
+            id,
+            resource_type: section.get_attribute("type")?.to_str()?,
+            path: section.get_attribute("path")?.to_str()?,
+            uid: section.get_attribute("uid").and_then(|x| x.to_str()),
+        })
+    }
+}
+
+/// Godot resource file (`.tscn` / `.tres`)
+#[derive(PartialEq, Debug, Clone)]
+pub struct GdResource(Vec<GdResourceSection>);
+
+impl GdResource {
+    /// Create a new wrapper
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - Sections, in file order
+    ///
+    pub fn new(sections: Vec<GdResourceSection>) -> Self {
+        Self(sections)
+    }
+
+    /// Get sections
+    pub fn sections(&self) -> &[GdResourceSection] {
+        &self.0
+    }
+
+    /// Get mutable sections
+    pub fn sections_mut(&mut self) -> &mut Vec<GdResourceSection> {
+        &mut self.0
+    }
+
+    /// Get header section (`gd_scene` or `gd_resource`)
+    pub fn header(&self) -> Option<&GdResourceSection> {
+        self.0.first()
+    }
+
+    /// Get sections by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Section name
+    ///
+    pub fn find_sections<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a GdResourceSection> + 'a {
+        self.0.iter().filter(move |s| s.name() == name)
+    }
+
+    /// Get external resource references
+    pub fn ext_resources(&self) -> Vec<GdExtResource> {
+        self.find_sections(EXT_RESOURCE_SECTION)
+            .filter_map(GdExtResource::from_section)
+            .collect()
+    }
+
+    /// Get external resource from its identifier
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Resource identifier
+    ///
+    pub fn get_ext_resource(&self, id: &str) -> Option<GdExtResource> {
+        self.ext_resources().into_iter().find(|r| r.id == id)
+    }
+}
+
+impl std::fmt::Display for GdResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serialize_gdresource(self))
+    }
+}
+
+impl FromStr for GdResource {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_gdresource_file(s)
+    }
+}
+
+/// Serialize a GdResource object to String
+///
+/// # Arguments
+///
+/// * `resource` - GdResource object
+///
+pub fn serialize_gdresource(resource: &GdResource) -> String {
+    let sections: Vec<_> = resource
+        .sections()
+        .iter()
+        .map(|section| {
+            let mut output = String::new();
+            output.push('[');
+            output.push_str(section.name());
+            for (k, v) in section.attributes() {
+                output.push(' ');
+                output.push_str(k);
+                output.push('=');
+                output.push_str(&v.to_string());
+            }
+            output.push(']');
+            output.push('\n');
+
+            for (k, v) in section.properties() {
+                output.push_str(k);
+                output.push_str(" = ");
+                output.push_str(&v.to_string());
+                output.push('\n');
+            }
+
+            output
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Parse Godot resource file
+///
+/// # Arguments
+///
+/// * `contents` - File contents
+///
+pub fn parse_gdresource_file(contents: &str) -> Result<GdResource, ParserError> {
+    let data = GdSettingsParser::parse(Rule::resource_file, contents)?
+        .next()
+        .ok_or(ParserError::ParseError)?;
+    let mut sections: Vec<GdResourceSection> = vec![];
+
+    for line in data.into_inner() {
+        match line.as_rule() {
+            Rule::resource_section => {
+                let mut inner_rules = line.into_inner();
+                let name = inner_rules.next().ok_or(ParserError::ParseError)?.as_str();
+                let mut section = GdResourceSection::new(name);
+
+                for attribute in inner_rules {
+                    let mut inner_rules = attribute.into_inner();
+                    let name = inner_rules.next().ok_or(ParserError::ParseError)?.as_str();
+                    let value = parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
+                    section.attributes.push((name.to_string(), value));
+                }
+
+                sections.push(section);
+            }
+            Rule::property => {
+                let span = line.as_span();
+                let section = sections.last_mut().ok_or_else(|| {
+                    pest::error::Error::new_from_span(
+                        ErrorVariant::<Rule>::CustomError {
+                            message: "Property defined outside of a section.".into(),
+                        },
+                        span,
+                    )
+                })?;
+
+                let mut inner_rules = line.into_inner();
+                let name = inner_rules
+                    .next()
+                    .ok_or(ParserError::ParseError)?
+                    .as_str()
+                    .to_string();
+                let value = parse_gdvalue(inner_rules.next().ok_or(ParserError::ParseError)?)?;
+                section.properties.push((name, value));
+            }
+            Rule::comment | Rule::EOI => (),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(GdResource::new(sections))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"[gd_scene load_steps=3 format=3 uid="uid://c7x2m4n5p6q"]
+
+[ext_resource type="Script" path="res://player.gd" id="1_abcde"]
+[ext_resource type="Texture2D" uid="uid://b8y3" path="res://icon.svg" id="2_fghij"]
+
+[sub_resource type="RectangleShape2D" id="RectangleShape2D_klmno"]
+size = Vector2(32.5, 16)
+
+[node name="Player" type="CharacterBody2D"]
+script = ExtResource("1_abcde")
+speed = 1e-05
+metadata/_edit_group_ = true
+
+[node name="Sprite" type="Sprite2D" parent="."]
+texture = ExtResource("2_fghij")
+animation = &"idle"
+"#;
+
+    #[test]
+    fn parser_test() {
+        let resource = parse_gdresource_file(SCENE).unwrap();
+
+        let header = resource.header().unwrap();
+        assert_eq!(header.name(), "gd_scene");
+        assert_eq!(header.get_attribute("format"), Some(GdValue::Int(3)));
+        assert_eq!(
+            header.get_attribute("uid"),
+            Some(GdValue::String("uid://c7x2m4n5p6q".into()))
+        );
+
+        assert_eq!(resource.find_sections("node").count(), 2);
+        let player = resource.find_sections("node").next().unwrap();
+        assert_eq!(
+            player.get_property("script"),
+            Some(GdValue::ClassInstance(
+                "ExtResource".into(),
+                vec![GdValue::String("1_abcde".into())],
+                vec![]
+            ))
+        );
+        assert_eq!(
+            player.get_property("metadata/_edit_group_"),
+            Some(GdValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn ext_resources_test() {
+        let resource = parse_gdresource_file(SCENE).unwrap();
+
+        assert_eq!(
+            resource.ext_resources(),
+            vec![
+                GdExtResource {
+                    id: "1_abcde".into(),
+                    resource_type: "Script".into(),
+                    path: "res://player.gd".into(),
+                    uid: None,
+                },
+                GdExtResource {
+                    id: "2_fghij".into(),
+                    resource_type: "Texture2D".into(),
+                    path: "res://icon.svg".into(),
+                    uid: Some("uid://b8y3".into()),
+                }
+            ]
+        );
+        assert_eq!(
+            resource.get_ext_resource("2_fghij").unwrap().path,
+            "res://icon.svg"
+        );
+    }
+
+    #[test]
+    fn edit_test() {
+        let mut resource = parse_gdresource_file(SCENE).unwrap();
+        let section = &mut resource.sections_mut()[1];
+        section.set_attribute("path", GdValue::String("res://hero.gd".into()));
+        section.set_property("metadata/note", GdValue::String("moved".into()));
+
+        let output = serialize_gdresource(&resource);
+        assert!(output.contains(
+            "[ext_resource type=\"Script\" path=\"res://hero.gd\" id=\"1_abcde\"]\nmetadata/note = \"moved\"\n"
+        ));
+
+        let section = &mut resource.sections_mut()[1];
+        section.remove_property("metadata/note").unwrap();
+        assert!(section.remove_property("metadata/note").is_err());
+    }
+
+    #[test]
+    fn serializer_test() {
+        let resource = parse_gdresource_file(SCENE).unwrap();
+        let output = serialize_gdresource(&resource);
+
+        assert!(output.starts_with(
+            "[gd_scene load_steps=3 format=3 uid=\"uid://c7x2m4n5p6q\"]\n\n[ext_resource"
+        ));
+        assert_eq!(parse_gdresource_file(&output).unwrap(), resource);
+    }
+
+    #[test]
+    fn property_outside_section_test() {
+        let error = parse_gdresource_file("a = 1\n[gd_resource]").unwrap_err();
+        let diagnostic = error.diagnostic().unwrap();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{fs, io::Read, path::Path};
+
+use gdsettings_parser::{parse_gdresource_file, serialize_gdresource, GdValue};
+
+#[test]
+fn main() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let resource_dirs = manifest_dir
+        .join("tests")
+        .join("samples")
+        .join("resource_files");
+
+    // Read each file
+    for entry in fs::read_dir(resource_dirs).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+
+        let mut string = String::new();
+        let mut input = fs::File::open(path).unwrap();
+        input.read_to_string(&mut string).unwrap();
+
+        // Start test
+        test_resource_file(&string);
+    }
+}
+
+fn test_resource_file(file_contents: &str) {
+    let data = parse_gdresource_file(file_contents).unwrap();
+
+    assert_eq!(
+        parse_gdresource_file(&serialize_gdresource(&data)).unwrap(),
+        data
+    );
+}
+
+#[test]
+fn test_64_bit_ints() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let contents = fs::read_to_string(
+        manifest_dir
+            .join("tests")
+            .join("samples")
+            .join("resource_files")
+            .join("area.tscn"),
+    )
+    .unwrap();
+
+    let data = parse_gdresource_file(&contents).unwrap();
+    let node = data.find_sections("node").next().unwrap();
+    assert_eq!(
+        node.get_property("collision_mask"),
+        Some(GdValue::Int(4294967295))
+    );
+    assert_eq!(
+        node.get_property("metadata/id"),
+        Some(GdValue::Int(-9007199254740991))
+    );
+    assert_eq!(node.get_property("collision_mask").unwrap().to_i32(), None);
+}
+
+#[test]
+fn test_typed_arrays() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let contents = fs::read_to_string(
+        manifest_dir
+            .join("tests")
+            .join("samples")
+            .join("resource_files")
+            .join("inventory.tscn"),
+    )
+    .unwrap();
+
+    let data = parse_gdresource_file(&contents).unwrap();
+    let node = data.find_sections("node").next().unwrap();
+    let ext_resource = |id: &str| {
+        GdValue::ClassInstance(
+            "ExtResource".into(),
+            vec![GdValue::String(id.into())],
+            vec![],
+        )
+    };
+
+    assert_eq!(
+        node.get_property("slot_sizes"),
+        Some(GdValue::TypedArray(
+            Box::new(GdValue::ClassName("int".into())),
+            vec![
+                GdValue::Int(1),
+                GdValue::Int(1),
+                GdValue::Int(4),
+                GdValue::Int(20)
+            ]
+        ))
+    );
+    assert_eq!(
+        node.get_property("items"),
+        Some(GdValue::TypedArray(
+            Box::new(ext_resource("2_7hq0v")),
+            vec![
+                ext_resource("3_m1c8r"),
+                GdValue::ClassInstance(
+                    "SubResource".into(),
+                    vec![GdValue::String("Resource_k2m4p".into())],
+                    vec![]
+                )
+            ]
+        ))
+    );
+    assert_eq!(
+        node.get_property("locked_slots"),
+        Some(GdValue::TypedArray(
+            Box::new(GdValue::ClassName("int".into())),
+            vec![]
+        ))
+    );
+
+    // Typed arrays are written back as Godot writes them
+    let serialized = serialize_gdresource(&data);
+    assert!(serialized.contains("slot_sizes = Array[int]([1, 1, 4, 20])\n"));
+    assert!(serialized.contains(
+        "items = Array[ExtResource(\"2_7hq0v\")]([ExtResource(\"3_m1c8r\"), SubResource(\"Resource_k2m4p\")])\n"
+    ));
+    assert!(serialized.contains("locked_slots = Array[int]([])\n"));
+}